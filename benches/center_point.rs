@@ -0,0 +1,38 @@
+use palette::rgb::Rgb;
+use spatial_led::{Sled, Vec2};
+use std::time::Duration;
+
+fn moving_center(c: &mut Criterion) {
+    let mut sled = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+
+    let simulated_duration = 15.0;
+    let simulated_hz = 60.0;
+    let total_steps = (simulated_duration * simulated_hz) as usize;
+    let mut d = 0.0;
+
+    c.bench_function("set_center_point", |b| {
+        b.iter(|| {
+            for step in 0..total_steps {
+                // walk the center around the room, as a tracked person might
+                let t = step as f32 / simulated_hz;
+                sled.set_center_point(Vec2::new(t.cos() * 1.5, 0.5 + t.sin()));
+
+                d = sled.closest().distance();
+            }
+        });
+    });
+    println!("{}", d); // prevent compiler from optimizing away center point updates
+}
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .significance_level(0.05)
+        .sample_size(30)
+        .warm_up_time(Duration::from_secs_f32(5.0))
+        .measurement_time(Duration::from_secs_f32(20.0));
+    targets = moving_center
+}
+criterion_main!(benches);
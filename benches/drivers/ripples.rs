@@ -20,7 +20,7 @@ pub fn build_driver() -> Driver<Rgb> {
     driver.set_startup_commands(startup);
     driver.set_compute_commands(compute);
     driver.set_draw_commands(draw);
-    return driver;
+    driver
 }
 
 fn startup(sled: &mut Sled<Rgb>, data: &mut Data) -> SledResult {
@@ -0,0 +1,140 @@
+use palette::rgb::Rgb;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use spatial_led::{Chain, Segment, Sled, SledBuilder, Vec2};
+use std::time::Duration;
+
+const NUM_PARTICLES: usize = 48;
+
+// a 10m x 10m ceiling of 20 zig-zagging runs, about 1,000 LEDs each
+fn build_ceiling() -> Sled<Rgb> {
+    let vertices = (0..=20).map(|row| {
+        let x = if row % 2 == 0 { 0.0 } else { 10.0 };
+        Vec2::new(x, row as f32 * 0.5)
+    });
+
+    SledBuilder::new(Vec2::new(5.0, 5.0), 99.95)
+        .chain(Chain::from_vertices(vertices))
+        .build()
+        .unwrap()
+}
+
+// the same ceiling with each run bowed into a curve, which sends furthest_from through the grid
+fn build_curved_ceiling() -> Sled<Rgb> {
+    let chain = (0..=20).fold(Chain::new(), |chain, row| {
+        let x = if row % 2 == 0 { 0.0 } else { 10.0 };
+        let y = row as f32 * 0.5;
+        chain.vertex_with(
+            Vec2::new(x, y),
+            Segment::quadratic_bezier(Vec2::new(5.0, y + 0.5)),
+        )
+    });
+
+    SledBuilder::new(Vec2::new(5.0, 5.0), 99.95)
+        .chain(chain)
+        .build()
+        .unwrap()
+}
+
+fn random_particles(rng: &mut StdRng) -> Vec<(Vec2, Vec2)> {
+    (0..NUM_PARTICLES)
+        .map(|_| {
+            let pos = Vec2::new(rng.gen_range(0.0..10.0), rng.gen_range(0.0..10.0));
+            let vel = Vec2::new(rng.gen_range(-1.0..1.0), rng.gen_range(-1.0..1.0));
+            (pos, vel)
+        })
+        .collect()
+}
+
+fn step(particles: &mut [(Vec2, Vec2)], timestep: f32) {
+    for (pos, vel) in particles {
+        *pos += *vel * timestep;
+        // bounce off the edges of the ceiling
+        if !(0.0..10.0).contains(&pos.x) {
+            vel.x = -vel.x;
+        }
+        if !(0.0..10.0).contains(&pos.y) {
+            vel.y = -vel.y;
+        }
+    }
+}
+
+fn particles(c: &mut Criterion) {
+    bench_layout(c, "straight", build_ceiling());
+    bench_layout(c, "curved", build_curved_ceiling());
+}
+
+fn bench_layout(c: &mut Criterion, name: &str, mut sled: Sled<Rgb>) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    let simulated_duration = 5.0;
+    let simulated_hz = 60.0;
+    let total_steps = (simulated_duration * simulated_hz) as usize;
+    let timestep = 1.0 / simulated_hz;
+
+    let mut group = c.benchmark_group(format!("particles, {} ({} LEDs)", name, sled.num_leds()));
+
+    let mut particles = random_particles(&mut rng);
+    group.bench_function("set_closest_to", |b| {
+        b.iter(|| {
+            for _ in 0..total_steps {
+                step(&mut particles, timestep);
+                for (pos, _) in &particles {
+                    sled.set_closest_to(*pos, Rgb::new(1.0, 1.0, 1.0));
+                }
+            }
+        });
+    });
+
+    let mut particles = random_particles(&mut rng);
+    group.bench_function("set_within_dist_from", |b| {
+        b.iter(|| {
+            for _ in 0..total_steps {
+                step(&mut particles, timestep);
+                for (pos, _) in &particles {
+                    sled.set_within_dist_from(0.25, *pos, Rgb::new(1.0, 0.5, 0.0));
+                }
+            }
+        });
+    });
+
+    let mut particles = random_particles(&mut rng);
+    group.bench_function("k_closest_to", |b| {
+        b.iter(|| {
+            for _ in 0..total_steps {
+                step(&mut particles, timestep);
+                for (pos, _) in &particles {
+                    let glow = sled.k_closest_to(*pos, 16);
+                    sled.modulate_filter(&glow, |led| led.color * 0.5);
+                }
+            }
+        });
+    });
+
+    let mut particles = random_particles(&mut rng);
+    group.bench_function("furthest_from", |b| {
+        b.iter(|| {
+            for _ in 0..total_steps {
+                step(&mut particles, timestep);
+                for (pos, _) in &particles {
+                    sled.set_furthest_from(*pos, Rgb::new(0.0, 0.0, 1.0));
+                }
+            }
+        });
+    });
+
+    group.finish();
+    println!("{:?}", sled.colors().next()); // prevent compiler from optimizing away the queries
+}
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default()
+        .significance_level(0.05)
+        .sample_size(30)
+        .warm_up_time(Duration::from_secs_f32(5.0))
+        .measurement_time(Duration::from_secs_f32(20.0));
+    targets = particles
+}
+criterion_main!(benches);
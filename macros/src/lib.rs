@@ -0,0 +1,108 @@
+//! Compile-time layout embedding for [spatial_led](https://docs.rs/spatial_led).
+//!
+//! Parsing a layout with `Sled::new_from_str(include_str!(...))` happens at runtime, so a typo in the config only shows up once the program is running,
+//! and the parser is compiled into the binary. [include_layout!] moves all of that to compile time instead.
+use proc_macro::{TokenStream, TokenTree};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use spatial_led::Sled;
+
+/// Reads a [config file](https://docs.rs/spatial_led/latest/spatial_led/struct.Sled.html#method.new) at compile time
+/// and expands to a `spatial_led::SledBuilder` that recreates its layout, without any parsing at runtime.
+///
+/// The path is relative to the directory containing the invoking crate's `Cargo.toml`. Syntax errors, along with anything else that would stop
+/// the layout from being built (like segments too short to hold any LEDs), are reported as compile errors, so on a device the resulting
+/// [`build()`](https://docs.rs/spatial_led/latest/spatial_led/struct.SledBuilder.html#method.build) can only fail if memory does.
+/// Editing the file triggers a rebuild, though editing a file it [includes](https://docs.rs/spatial_led/latest/spatial_led/struct.Sled.html#method.new) doesn't.
+///
+/// ```rust
+/// use spatial_led::{Sled, SledBuilder};
+/// use spatial_led_macros::include_layout;
+///
+/// let builder: SledBuilder = include_layout!("../benches/config.yap");
+/// let sled = builder.build::<(u8, u8, u8)>().unwrap();
+///
+/// let parsed = Sled::<(u8, u8, u8)>::new("../benches/config.yap").unwrap();
+/// assert_eq!(sled.to_config_string(), parsed.to_config_string());
+/// ```
+///
+/// ```compile_fail
+/// // fails to compile, as the file doesn't exist.
+/// let builder = spatial_led_macros::include_layout!("missing.yap");
+/// ```
+#[proc_macro]
+pub fn include_layout(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(expansion) => expansion,
+        Err(message) => compile_error(&message),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let relative_path = path_literal(input)?;
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR")
+        .map_err(|_| "include_layout! must be invoked through cargo.".to_string())?;
+    let path = PathBuf::from(manifest_dir).join(&relative_path);
+
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| format!("`{}` isn't a valid UTF-8 path.", path.display()))?;
+    // loading through Sled resolves includes relative to the file, and checks the layout can be built.
+    let builder = Sled::<()>::new(path_str)
+        .map_err(|e| format!("Invalid layout in `{}`: {}", relative_path, e))?
+        .to_builder();
+
+    // include_str! tells cargo to rebuild whenever the file changes.
+    let expansion = format!(
+        "{{ const _: &str = ::core::include_str!({:?}); {} }}",
+        path_str,
+        builder.to_rust_source("::spatial_led")
+    );
+    TokenStream::from_str(&expansion).map_err(|e| e.to_string())
+}
+
+/// Extracts the contents of a single string literal, like `"layout.yap"` or `r"layout.yap"`.
+fn path_literal(input: TokenStream) -> Result<String, String> {
+    let expected =
+        || "Expected a path to a layout, like `include_layout!(\"layout.yap\")`.".to_string();
+
+    let mut tokens = input.into_iter();
+    let literal = match (tokens.next(), tokens.next()) {
+        (Some(TokenTree::Literal(literal)), None) => literal.to_string(),
+        // literals passed through `macro_rules!` arrive wrapped in an invisible group.
+        (Some(TokenTree::Group(group)), None) => return path_literal(group.stream()),
+        _ => return Err(expected()),
+    };
+
+    if let Some(raw) = literal.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        return raw[hashes..raw.len() - hashes]
+            .strip_prefix('"')
+            .and_then(|s| s.strip_suffix('"'))
+            .map(str::to_string)
+            .ok_or_else(expected);
+    }
+
+    let quoted = literal
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(expected)?;
+
+    let mut path = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(escaped @ ('\\' | '"' | '\'')) => path.push(escaped),
+                _ => return Err("Only `\\\\`, `\\\"` and `\\'` escapes are supported in layout paths; try a raw string.".to_string()),
+            },
+            c => path.push(c),
+        }
+    }
+    Ok(path)
+}
+
+fn compile_error(message: &str) -> TokenStream {
+    TokenStream::from_str(&format!("::core::compile_error!({:?});", message)).unwrap()
+}
@@ -0,0 +1,40 @@
+use spatial_led::Sled;
+use spatial_led_macros::include_layout;
+
+#[test]
+fn embedded_layouts_match_parsed_ones() {
+    let embedded = include_layout!("fixtures/everything.yap")
+        .build::<(u8, u8, u8)>()
+        .unwrap();
+    let parsed = Sled::<(u8, u8, u8)>::new("fixtures/everything.yap").unwrap();
+
+    assert_eq!(embedded.to_config_string(), parsed.to_config_string());
+    assert!(embedded.positions().eq(parsed.positions()));
+    assert_eq!(
+        embedded.vertex_by_name("top").unwrap().index(),
+        parsed.vertex_by_name("top").unwrap().index()
+    );
+    assert_eq!(
+        embedded.filter_by_tag("zone", "bar").len(),
+        parsed.filter_by_tag("zone", "bar").len()
+    );
+    assert_eq!(
+        embedded.group("lobby").unwrap(),
+        parsed.group("lobby").unwrap()
+    );
+}
+
+#[test]
+fn paths_can_be_passed_through_other_macros() {
+    macro_rules! wrapped {
+        ($path:literal) => {
+            include_layout!($path)
+        };
+    }
+
+    let sled = wrapped!(r"../benches/config.yap")
+        .build::<(u8, u8, u8)>()
+        .unwrap();
+    let parsed = Sled::<(u8, u8, u8)>::new("../benches/config.yap").unwrap();
+    assert_eq!(sled.num_leds(), parsed.num_leds());
+}
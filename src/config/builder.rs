@@ -0,0 +1,831 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::str::FromStr;
+
+use crate::{
+    color::ColorType,
+    config::{
+        is_valid_coordinate, is_valid_density, is_valid_led_count, is_valid_name, is_valid_tag_key,
+        ChainConfig, Config, Gaps, LedCount, LineSegment, Lint, SegmentKind, Tags, DEFAULT_OUTPUT,
+    },
+    error::SledError,
+    spatial_led::Sled,
+    Vec2, Vec3,
+};
+
+/// Constructs a [Sled] from layout information supplied in code, rather than from a [config file](Sled::new).
+///
+/// Everything that can be expressed in a config file can be expressed with a builder, and both are held to the same validation rules.
+/// ```rust
+/// # use spatial_led::{Sled, SledBuilder, Chain, Segment, SledError, Vec2};
+/// # use palette::rgb::Rgb;
+/// # fn main() -> Result<(), SledError> {
+/// // equivalent to:
+/// //  center: (0, 0.5)
+/// //  density: 30
+/// //  --segments--
+/// //  (-2, 0) --> (0.5, -1) --> (3.5, 0) |
+/// //  [density: 60] (2, 2) --> [arc: (0, 3), leds: 47] (-2, 2)
+/// let sled: Sled<Rgb> = SledBuilder::new(Vec2::new(0.0, 0.5), 30.0)
+///     .chain(Chain::from_vertices([
+///         Vec2::new(-2.0, 0.0),
+///         Vec2::new(0.5, -1.0),
+///         Vec2::new(3.5, 0.0),
+///     ]))
+///     .chain(
+///         Chain::new()
+///             .density(60.0)
+///             .vertex(Vec2::new(2.0, 2.0))
+///             .vertex_with(
+///                 Vec2::new(-2.0, 2.0),
+///                 Segment::arc(Vec2::new(0.0, 3.0)).leds(47),
+///             ),
+///     )
+///     .build()?;
+///
+/// assert_eq!(sled.num_segments(), 3);
+/// assert_eq!(sled.segment(2).unwrap().len(), 47);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SledBuilder {
+    center_point: Vec2,
+    #[cfg_attr(feature = "serde", serde(default))]
+    center_z: f32,
+    density: f32,
+    chains: Vec<Chain>,
+}
+
+/// A sequence of connected vertices, equivalent to the vertices between two `|` breaks in a config file. See [SledBuilder].
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(default)
+)]
+pub struct Chain {
+    vertices: Vec<Vec2>,
+    // segments[i] describes the segment ending at vertices[i + 1]
+    segments: Vec<Segment>,
+    density: Option<f32>,
+    reversed: bool,
+    offset: usize,
+    output: Option<String>,
+    name: Option<String>,
+    // labels for vertices, keyed by their position in `vertices`
+    vertex_names: BTreeMap<usize, String>,
+    // heights of vertices off the z = 0 plane, keyed by their position in `vertices`
+    heights: BTreeMap<usize, f32>,
+    tags: Tags,
+    group: Option<String>,
+}
+
+/// Describes the shape, LED count and gaps of one segment in a [Chain]. See [SledBuilder].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Segment {
+    kind: SegmentKind,
+    density: Option<f32>,
+    leds: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    gaps: Gaps,
+    #[cfg_attr(feature = "serde", serde(default))]
+    name: Option<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tags: Tags,
+}
+
+impl SledBuilder {
+    /// Starts a new layout with the given center point and default LED density.
+    pub fn new(center_point: Vec2, density: f32) -> Self {
+        SledBuilder {
+            center_point,
+            center_z: 0.0,
+            density,
+            chains: vec![],
+        }
+    }
+
+    /// Sets the height of the center point, for 3D layouts. See [Chain::vertex_z()].
+    pub fn center_z(mut self, z: f32) -> Self {
+        self.center_z = z;
+        self
+    }
+
+    /// Appends a chain of connected segments to the layout.
+    pub fn chain(mut self, chain: Chain) -> Self {
+        self.chains.push(chain);
+        self
+    }
+
+    /// Validates the layout and constructs a [Sled] from it.
+    ///
+    /// Returns an [error](SledError) if any density or LED count is not positive, a vertex is not finite,
+    /// an arc can't be drawn through its points, a segment is too short to hold any LEDs, or the layout doesn't contain any segments.
+    pub fn build<COLOR: ColorType>(self) -> Result<Sled<COLOR>, SledError> {
+        let config = self.into_config()?;
+        Sled::new_from_config(config)
+    }
+
+    /// Checks the layout for likely mistakes without building it, such as segments too short to hold any LEDs,
+    /// duplicated segments, or chains whose ends almost, but don't quite, meet. See [LintKind] for the full list.
+    ///
+    /// Lints with [LintSeverity::Error] would cause [SledBuilder::build()] to fail; warnings wouldn't.
+    /// Returns an [error](SledError) if the layout is invalid for any other reason.
+    ///
+    /// A config file can be linted by parsing it into a builder first.
+    ///
+    /// O(SEGMENTS^2)
+    /// ```rust
+    /// # use spatial_led::{SledBuilder, LintKind, LintSeverity};
+    /// let builder: SledBuilder = "
+    ///     center: (0, 0)
+    ///     density: 30
+    ///     --segments--
+    ///     (0, 0) --> (1, 0) --> (1, 1) |
+    ///     (1.01, 1) --> (2, 1) | # almost continues the first chain
+    ///     (0, 0) --> (1, 0)      # already drawn above
+    /// ".parse().unwrap();
+    ///
+    /// let lints = builder.lint().unwrap();
+    /// assert_eq!(lints.len(), 2);
+    /// assert_eq!(lints[0].kind, LintKind::Overlapping);
+    /// assert_eq!(lints[0].segments, [0, 3]);
+    /// assert_eq!(lints[1].kind, LintKind::NearlyConnected);
+    /// assert_eq!(lints[1].segments, [1, 2]);
+    /// assert!(lints.iter().all(|lint| lint.severity() == LintSeverity::Warning));
+    /// ```
+    pub fn lint(&self) -> Result<Vec<Lint>, SledError> {
+        Ok(self.clone().into_config()?.lint())
+    }
+
+    /// Describes an existing layout.
+    pub(crate) fn from_layout(
+        center_point: Vec2,
+        center_z: f32,
+        density: f32,
+        line_segments: &[LineSegment],
+        chains: &[ChainConfig],
+    ) -> Self {
+        let mut builder = SledBuilder::new(center_point, density).center_z(center_z);
+        let mut output = DEFAULT_OUTPUT;
+
+        for chain_config in chains {
+            let segments = &line_segments[chain_config.segments.clone()];
+            let mut chain = Chain::new()
+                .vertex(segments[0].start)
+                .offset(chain_config.offset);
+            chain.reversed = chain_config.reversed;
+            chain.name = chain_config.name.clone();
+            chain.group = chain_config.group.clone();
+            if let Some(name) = &chain_config.start_name {
+                chain = chain.vertex_name(name);
+            }
+            if segments[0].start_z != 0.0 {
+                chain = chain.vertex_z(segments[0].start_z);
+            }
+            if chain_config.output != output {
+                chain = chain.output(&chain_config.output);
+                output = &chain_config.output;
+            }
+
+            for segment in segments {
+                chain = chain.vertex_with(segment.end, Segment::describe(segment, density));
+                if let Some(name) = &segment.end_name {
+                    chain = chain.vertex_name(name);
+                }
+                if segment.end_z != 0.0 {
+                    chain = chain.vertex_z(segment.end_z);
+                }
+            }
+            builder = builder.chain(chain);
+        }
+
+        builder
+    }
+
+    fn into_config(self) -> Result<Config, SledError> {
+        if !is_valid_vertex(self.center_point) || !is_valid_coordinate(self.center_z) {
+            return SledError::new(format!(
+                "Invalid center point {}; coordinates must be finite.",
+                self.center_point.extend(self.center_z)
+            ))
+            .as_err();
+        }
+
+        if !is_valid_density(self.density) {
+            return SledError::new(format!(
+                "Invalid density of {}; density must be a positive number.",
+                self.density
+            ))
+            .as_err();
+        }
+
+        let mut line_segments = vec![];
+        let mut chains = vec![];
+        let mut output = DEFAULT_OUTPUT;
+        let mut labels = BTreeSet::new();
+        for (chain_index, chain) in self.chains.iter().enumerate() {
+            let chain_density = match chain.density {
+                Some(density) if !is_valid_density(density) => {
+                    return SledError::new(format!(
+                        "Invalid density of {} on chain {}; density must be a positive number.",
+                        density, chain_index
+                    ))
+                    .as_err();
+                }
+                Some(density) => density,
+                None => self.density,
+            };
+
+            if let Some(name) = &chain.output {
+                if !is_valid_name(name) {
+                    return SledError::new(format!(
+                        "Invalid output name `{}` on chain {}; names must be non-empty and can't contain whitespace or any of `,[]():#|`.",
+                        name, chain_index
+                    ))
+                    .as_err();
+                }
+                output = name;
+            }
+
+            if let Some(index) = chain
+                .vertex_names
+                .keys()
+                .chain(chain.heights.keys())
+                .find(|index| **index >= chain.vertices.len())
+            {
+                return SledError::new(format!(
+                    "Chain {} names or raises vertex {}, but only has {} vertices.",
+                    chain_index,
+                    index,
+                    chain.vertices.len()
+                ))
+                .as_err();
+            }
+
+            if let Some(group) = chain.group.as_ref().filter(|group| !is_valid_group(group)) {
+                return SledError::new(format!(
+                    "Invalid group `{}` on chain {}; groups are names, optionally joined by `/`.",
+                    group, chain_index
+                ))
+                .as_err();
+            }
+
+            let names = chain
+                .name
+                .iter()
+                .chain(
+                    chain
+                        .segments
+                        .iter()
+                        .filter_map(|segment| segment.name.as_ref()),
+                )
+                .chain(chain.vertex_names.values());
+            for name in names {
+                if !is_valid_name(name) {
+                    return SledError::new(format!(
+                        "Invalid name `{}` on chain {}; names must be non-empty and can't contain whitespace or any of `,[]():#|`.",
+                        name, chain_index
+                    ))
+                    .as_err();
+                }
+                if !labels.insert(name.as_str()) {
+                    return SledError::new(format!(
+                        "The name `{}` is used more than once; each chain, segment and vertex needs a name of its own.",
+                        name
+                    ))
+                    .as_err();
+                }
+            }
+
+            let tags = chain
+                .tags
+                .iter()
+                .chain(chain.segments.iter().flat_map(|segment| &segment.tags));
+            for (key, value) in tags {
+                if !is_valid_tag_key(key) || !is_valid_name(value) {
+                    return SledError::new(format!(
+                        "Invalid tag `{}={}` on chain {}; keys and values must be non-empty and can't contain whitespace or any of `,[]():#|`, and keys can't contain `=`.",
+                        key, value, chain_index
+                    ))
+                    .as_err();
+                }
+            }
+
+            if let Some(vertex) = chain.vertices.iter().find(|v| !is_valid_vertex(**v)) {
+                return SledError::new(format!(
+                    "Invalid vertex {} on chain {}; coordinates must be finite.",
+                    vertex, chain_index
+                ))
+                .as_err();
+            }
+            if let Some((index, z)) = chain
+                .heights
+                .iter()
+                .find(|(_, z)| !is_valid_coordinate(**z))
+            {
+                return SledError::new(format!(
+                    "Invalid height {} for vertex {} on chain {}; coordinates must be finite.",
+                    z, index, chain_index
+                ))
+                .as_err();
+            }
+
+            // only reachable through deserialization; the Chain API keeps these in sync.
+            if chain.segments.len() + 1 != chain.vertices.len().max(1) {
+                return SledError::new(format!(
+                    "Chain {} has {} vertices but {} segments; expected one segment between each pair of vertices.",
+                    chain_index,
+                    chain.vertices.len(),
+                    chain.segments.len()
+                ))
+                .as_err();
+            }
+
+            for (segment_index, (ends, segment)) in
+                chain.vertices.windows(2).zip(&chain.segments).enumerate()
+            {
+                let height = |vertex| chain.heights.get(&vertex).copied().unwrap_or(0.0);
+                let line_segment = segment
+                    .to_line_segment(
+                        ends[0].extend(height(segment_index)),
+                        ends[1].extend(height(segment_index + 1)),
+                        chain_density,
+                    )
+                    .map_err(|problem| {
+                        SledError::new(format!(
+                            "Invalid segment {} on chain {}; {}",
+                            segment_index, chain_index, problem
+                        ))
+                    })?;
+                line_segments.push(line_segment);
+                // chain-wide tags apply to every segment, unless the segment sets its own value.
+                let tags = &mut line_segments.last_mut().unwrap().tags;
+                for (key, value) in &chain.tags {
+                    tags.entry(key.clone()).or_insert_with(|| value.clone());
+                }
+            }
+
+            let first_segment = line_segments.len() - chain.segments.len();
+            for (vertex_index, name) in &chain.vertex_names {
+                if let Some(segment) = vertex_index
+                    .checked_sub(1)
+                    .and_then(|i| line_segments.get_mut(first_segment + i))
+                {
+                    segment.end_name = Some(name.clone());
+                }
+            }
+
+            if !chain.segments.is_empty() {
+                chains.push(ChainConfig {
+                    segments: line_segments.len() - chain.segments.len()..line_segments.len(),
+                    reversed: chain.reversed,
+                    offset: chain.offset,
+                    output: output.into(),
+                    name: chain.name.clone(),
+                    start_name: chain.vertex_names.get(&0).cloned(),
+                    group: chain.group.clone(),
+                });
+            }
+        }
+
+        if line_segments.is_empty() {
+            return SledError::new("A Sled needs at least one line segment.".into()).as_err();
+        }
+
+        Ok(Config {
+            center_point: self.center_point,
+            center_z: self.center_z,
+            density: self.density,
+            line_segments,
+            chains,
+        })
+    }
+}
+
+impl SledBuilder {
+    /// Writes out Rust source for an expression that recreates this builder through the public builder API,
+    /// with every path prefixed by `krate` (e.g. `::spatial_led`). Used by `spatial_led_macros` to embed layouts at compile time.
+    ///
+    /// Floats are written with their shortest round-tripping representation, so no precision is lost.
+    #[doc(hidden)]
+    pub fn to_rust_source(&self, krate: &str) -> String {
+        let vertex = |v: Vec2| format!("{}::Vec2::new({:?}f32, {:?}f32)", krate, v.x, v.y);
+
+        let mut out = format!(
+            "{}::SledBuilder::new({}, {:?}f32)",
+            krate,
+            vertex(self.center_point),
+            self.density
+        );
+        if self.center_z != 0.0 {
+            out.push_str(&format!(".center_z({:?}f32)", self.center_z));
+        }
+
+        for chain in &self.chains {
+            let mut source = format!("{}::Chain::new()", krate);
+            if let Some(name) = &chain.name {
+                source.push_str(&format!(".name({:?})", name));
+            }
+            for (key, value) in &chain.tags {
+                source.push_str(&format!(".tag({:?}, {:?})", key, value));
+            }
+            if let Some(density) = chain.density {
+                source.push_str(&format!(".density({:?}f32)", density));
+            }
+            if chain.reversed {
+                source.push_str(".reversed()");
+            }
+            if chain.offset > 0 {
+                source.push_str(&format!(".offset({})", chain.offset));
+            }
+            if let Some(output) = &chain.output {
+                source.push_str(&format!(".output({:?})", output));
+            }
+            if let Some(group) = &chain.group {
+                source.push_str(&format!(".group({:?})", group));
+            }
+
+            for (index, v) in chain.vertices.iter().enumerate() {
+                match index.checked_sub(1).and_then(|i| chain.segments.get(i)) {
+                    Some(segment) => source.push_str(&format!(
+                        ".vertex_with({}, {})",
+                        vertex(*v),
+                        segment.to_rust_source(krate, &vertex)
+                    )),
+                    None => source.push_str(&format!(".vertex({})", vertex(*v))),
+                }
+                if let Some(name) = chain.vertex_names.get(&index) {
+                    source.push_str(&format!(".vertex_name({:?})", name));
+                }
+                if let Some(z) = chain.heights.get(&index) {
+                    source.push_str(&format!(".vertex_z({:?}f32)", z));
+                }
+            }
+
+            out.push_str(&format!(".chain({})", source));
+        }
+
+        out
+    }
+}
+
+/// Parses a layout in the [config file](Sled::new) format, so it can be modified or [linted](SledBuilder::lint) before it's built.
+impl FromStr for SledBuilder {
+    type Err = SledError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let config = Config::from_str(s)?;
+        Ok(SledBuilder::from_layout(
+            config.center_point,
+            config.center_z,
+            config.density,
+            &config.line_segments,
+            &config.chains,
+        ))
+    }
+}
+
+impl Chain {
+    /// Creates an empty chain. Add vertices to it with [Chain::vertex()] and [Chain::vertex_with()].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a chain of straight segments connecting each of the given vertices in order.
+    pub fn from_vertices(vertices: impl IntoIterator<Item = Vec2>) -> Self {
+        vertices
+            .into_iter()
+            .fold(Chain::new(), |chain, vertex| chain.vertex(vertex))
+    }
+
+    /// Creates a chain with one LED at each of the given points, running from the first point to the last.
+    /// See [Segment::points()].
+    pub fn from_points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        let points: Vec<Vec2> = points.into_iter().collect();
+        match (points.first(), points.last()) {
+            (Some(first), Some(last)) => Chain::new()
+                .vertex(*first)
+                .vertex_with(*last, Segment::points(points.iter().copied())),
+            _ => Chain::new(),
+        }
+    }
+
+    /// Overrides the [SledBuilder]'s default density for every segment in this chain.
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    /// Declares that the data line enters this chain at its last vertex rather than its first,
+    /// so LED indices run backwards along it. Positions are unaffected.
+    pub fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    /// Declares that the data line enters this chain `offset` LEDs along it (after [reversing](Chain::reversed), if set),
+    /// wrapping around to the chain's start once it reaches the end. Offsets larger than the chain's LED count wrap around too.
+    pub fn offset(mut self, offset: usize) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Assigns this chain, and every chain after it that doesn't name its own, to the named output.
+    /// Chains before the first named output are assigned to an output called `default`. See [Sled::outputs()].
+    pub fn output(mut self, name: impl Into<String>) -> Self {
+        self.output = Some(name.into());
+        self
+    }
+
+    /// Labels this chain, so its LEDs can be looked up with [Sled::chain_by_name()] rather than by index.
+    ///
+    /// Names must be unique across every chain, segment and vertex in the layout.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Tags every segment in this chain with `key=value`, unless the segment sets its own value for `key`.
+    /// See [Segment::tag()].
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    /// Puts this chain in a group, like the ones made by [including](Sled::new) another layout file. See [Sled::group()].
+    ///
+    /// Nested groups are written as a path of names, like `venue/kitchen`.
+    pub fn group(mut self, group: impl Into<String>) -> Self {
+        self.group = Some(group.into());
+        self
+    }
+
+    /// Labels the most recently added vertex, so it can be looked up with [Sled::vertex_by_name()].
+    /// Does nothing if the chain has no vertices yet.
+    pub fn vertex_name(mut self, name: impl Into<String>) -> Self {
+        if let Some(last) = self.vertices.len().checked_sub(1) {
+            self.vertex_names.insert(last, name.into());
+        }
+        self
+    }
+
+    /// Raises the most recently added vertex `z` units off the `z = 0` plane, for 3D layouts.
+    /// Segments rise or fall linearly between their vertices' heights. Does nothing if the chain has no vertices yet.
+    ///
+    /// Planar [Sleds](Sled) ignore heights; see `Sled3`, behind the `3d` feature.
+    pub fn vertex_z(mut self, z: f32) -> Self {
+        if let Some(last) = self.vertices.len().checked_sub(1) {
+            self.heights.insert(last, z);
+        }
+        self
+    }
+
+    /// Appends a vertex, connected to the previous one by a straight segment.
+    pub fn vertex(self, vertex: Vec2) -> Self {
+        self.vertex_with(vertex, Segment::line())
+    }
+
+    /// Appends a vertex, connected to the previous one by the given segment.
+    ///
+    /// The segment is ignored if this is the first vertex in the chain.
+    pub fn vertex_with(mut self, vertex: Vec2, segment: Segment) -> Self {
+        if !self.vertices.is_empty() {
+            self.segments.push(segment);
+        }
+        self.vertices.push(vertex);
+        self
+    }
+}
+
+impl Segment {
+    /// A straight segment.
+    pub fn line() -> Self {
+        Segment {
+            kind: SegmentKind::Line,
+            density: None,
+            leds: None,
+            gaps: Gaps::None,
+            name: None,
+            tags: Tags::new(),
+        }
+    }
+
+    /// A circular arc passing through `through`. If the segment starts and ends at the same point, a full circle with `through` on the opposite side.
+    pub fn arc(through: Vec2) -> Self {
+        Segment {
+            kind: SegmentKind::Arc(through),
+            ..Segment::line()
+        }
+    }
+
+    /// A quadratic Bézier curve with one control point.
+    pub fn quadratic_bezier(control: Vec2) -> Self {
+        Segment {
+            kind: SegmentKind::QuadraticBezier(control),
+            ..Segment::line()
+        }
+    }
+
+    /// A cubic Bézier curve with two control points.
+    pub fn cubic_bezier(control_1: Vec2, control_2: Vec2) -> Self {
+        Segment {
+            kind: SegmentKind::CubicBezier(control_1, control_2),
+            ..Segment::line()
+        }
+    }
+
+    /// One LED at each of the given points, in order, rather than LEDs spaced evenly along a path.
+    /// Useful for irregular fixtures whose LED positions were measured, e.g. by a camera mapping tool.
+    ///
+    /// The number of points determines the segment's LED count, so [Segment::density()] and [Segment::leds()] can't be used alongside it.
+    pub fn points(points: impl IntoIterator<Item = Vec2>) -> Self {
+        Segment {
+            kind: SegmentKind::Points(points.into_iter().collect()),
+            ..Segment::line()
+        }
+    }
+
+    /// Overrides the chain's density for this segment.
+    pub fn density(mut self, density: f32) -> Self {
+        self.density = Some(density);
+        self
+    }
+
+    /// Pins this segment to exactly `leds` LEDs, regardless of its length or density.
+    pub fn leds(mut self, leds: usize) -> Self {
+        self.leds = Some(leds);
+        self
+    }
+
+    /// Marks every LED on this segment as a gap: wired into the data line, but hidden or dead.
+    ///
+    /// Gaps keep their slot in the output, but are excluded from spatial queries and always output `COLOR::default()`.
+    pub fn gap(mut self) -> Self {
+        self.gaps = Gaps::All;
+        self
+    }
+
+    /// Marks runs of LEDs on this segment as [gaps](Segment::gap), counted from the segment's start.
+    pub fn gaps(mut self, runs: impl IntoIterator<Item = Range<usize>>) -> Self {
+        self.gaps = Gaps::Runs(runs.into_iter().collect());
+        self
+    }
+
+    /// Labels this segment, so its LEDs can be looked up with [Sled::segment_by_name()] rather than by index.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Attaches an arbitrary fact to this segment's LEDs, like `zone=bar` or `facing=down`.
+    /// See [Sled::tags_of()] and [Sled::filter_by_tag()].
+    ///
+    /// Keys and values follow the same rules as names, and keys can't contain `=`.
+    pub fn tag(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.tags.insert(key.into(), value.into());
+        self
+    }
+
+    fn to_rust_source(&self, krate: &str, vertex: &impl Fn(Vec2) -> String) -> String {
+        let mut out = match &self.kind {
+            SegmentKind::Line => format!("{}::Segment::line()", krate),
+            SegmentKind::Arc(through) => format!("{}::Segment::arc({})", krate, vertex(*through)),
+            SegmentKind::QuadraticBezier(control) => {
+                format!("{}::Segment::quadratic_bezier({})", krate, vertex(*control))
+            }
+            SegmentKind::CubicBezier(control_1, control_2) => format!(
+                "{}::Segment::cubic_bezier({}, {})",
+                krate,
+                vertex(*control_1),
+                vertex(*control_2)
+            ),
+            SegmentKind::Points(points) => {
+                let points: Vec<String> = points.iter().map(|point| vertex(*point)).collect();
+                format!("{}::Segment::points([{}])", krate, points.join(", "))
+            }
+        };
+
+        if let Some(density) = self.density {
+            out.push_str(&format!(".density({:?}f32)", density));
+        }
+        if let Some(leds) = self.leds {
+            out.push_str(&format!(".leds({})", leds));
+        }
+        match &self.gaps {
+            Gaps::None => {}
+            Gaps::All => out.push_str(".gap()"),
+            Gaps::Runs(runs) => {
+                let runs: Vec<String> = runs
+                    .iter()
+                    .map(|run| format!("{}..{}", run.start, run.end))
+                    .collect();
+                out.push_str(&format!(".gaps([{}])", runs.join(", ")));
+            }
+        }
+        if let Some(name) = &self.name {
+            out.push_str(&format!(".name({:?})", name));
+        }
+        for (key, value) in &self.tags {
+            out.push_str(&format!(".tag({:?}, {:?})", key, value));
+        }
+
+        out
+    }
+
+    /// Describes an existing segment, leaving the density unset if it matches the layout's default.
+    fn describe(segment: &LineSegment, density: f32) -> Self {
+        let description = Segment {
+            kind: segment.kind.clone(),
+            gaps: segment.gaps.clone(),
+            name: segment.name.clone(),
+            tags: segment.tags.clone(),
+            ..Segment::line()
+        };
+        match segment.led_count {
+            _ if matches!(segment.kind, SegmentKind::Points(_)) => description,
+            LedCount::Density(d) if d.to_bits() == density.to_bits() => description,
+            LedCount::Density(d) => description.density(d),
+            LedCount::Exact(leds) => description.leds(leds),
+        }
+    }
+
+    fn to_line_segment(
+        &self,
+        start: Vec3,
+        end: Vec3,
+        chain_density: f32,
+    ) -> Result<LineSegment, &'static str> {
+        let control_points_valid = match &self.kind {
+            SegmentKind::Line => true,
+            SegmentKind::Arc(point) | SegmentKind::QuadraticBezier(point) => {
+                is_valid_vertex(*point)
+            }
+            SegmentKind::CubicBezier(a, b) => is_valid_vertex(*a) && is_valid_vertex(*b),
+            SegmentKind::Points(points) => points.iter().all(|point| is_valid_vertex(*point)),
+        };
+        if !control_points_valid {
+            return Err("control point coordinates must be finite.");
+        }
+
+        let led_count = match (&self.kind, self.leds, self.density) {
+            (SegmentKind::Points(_), Some(_), _) | (SegmentKind::Points(_), _, Some(_)) => {
+                return Err("density and leds can't be set on a segment of points.")
+            }
+            (SegmentKind::Points(points), ..) if points.is_empty() => {
+                return Err("a segment of points needs at least one point.")
+            }
+            (SegmentKind::Points(points), ..) => LedCount::Exact(points.len()),
+            (_, Some(_), Some(_)) => return Err("only one of density or leds may be set."),
+            (_, Some(leds), _) if !is_valid_led_count(leds) => {
+                return Err("LED count must be a positive number.")
+            }
+            (_, Some(leds), _) => LedCount::Exact(leds),
+            (_, _, Some(density)) if !is_valid_density(density) => {
+                return Err("density must be a positive number.")
+            }
+            (_, _, Some(density)) => LedCount::Density(density),
+            (_, None, None) => LedCount::Density(chain_density),
+        };
+
+        let mut segment = LineSegment::new(
+            start.truncate(),
+            end.truncate(),
+            self.kind.clone(),
+            led_count,
+        )
+        .ok_or("arcs must pass through three distinct, non-collinear points.")?;
+        segment.start_z = start.z;
+        segment.end_z = end.z;
+
+        if self.gaps.first_invalid_run(segment.num_leds()).is_some() {
+            return Err("gap runs must be non-empty and within the segment's LEDs.");
+        }
+        segment.gaps = self.gaps.clone();
+        segment.name = self.name.clone();
+        segment.tags = self.tags.clone();
+
+        Ok(segment)
+    }
+}
+
+impl Default for Segment {
+    fn default() -> Self {
+        Segment::line()
+    }
+}
+
+fn is_valid_group(group: &str) -> bool {
+    group.split('/').all(is_valid_name)
+}
+
+fn is_valid_vertex(vertex: Vec2) -> bool {
+    is_valid_coordinate(vertex.x) && is_valid_coordinate(vertex.y)
+}
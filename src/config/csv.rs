@@ -0,0 +1,95 @@
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{
+    config::is_valid_coordinate,
+    error::{ConfigError, ConfigErrorKind},
+    Vec2,
+};
+
+/// Parses measured LED positions from rows of `x, y` or `x, y, label`.
+///
+/// Returns the points grouped into segments, where consecutive rows sharing a label belong to the same segment.
+/// Blank lines are ignored, as is a header row if the first row doesn't start with a number.
+pub(crate) fn parse_points(csv: &str) -> Result<Vec<Vec<Vec2>>, ConfigError> {
+    let mut segments: Vec<Vec<Vec2>> = vec![];
+    let mut last_label = None;
+
+    for (line_index, line) in csv.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let fields = fields(line);
+        let is_first_row = last_label.is_none();
+        if is_first_row && fields[0].1.parse::<f32>().is_err() {
+            // not data, so it must be a header
+            last_label = Some("");
+            continue;
+        }
+
+        let line_number = line_index + 1;
+        let error = |(column, found): (usize, &str), kind, expected| ConfigError {
+            kind,
+            line: line_number,
+            column,
+            found: found.to_string(),
+            expected,
+        };
+
+        let coordinate = |field: Option<&(usize, &str)>| match field {
+            Some((column, text)) => text
+                .parse::<f32>()
+                .ok()
+                .filter(|c| is_valid_coordinate(*c))
+                .ok_or_else(|| error((*column, text), ConfigErrorKind::InvalidNumber, "a number")),
+            None => Err(error(
+                (line.chars().count() + 1, ""),
+                ConfigErrorKind::UnexpectedEnd,
+                "`x, y` or `x, y, label`",
+            )),
+        };
+
+        let point = Vec2::new(coordinate(fields.first())?, coordinate(fields.get(1))?);
+        let label = fields.get(2).map_or("", |(_, label)| *label);
+        if let Some(extra) = fields.get(3) {
+            return Err(error(
+                *extra,
+                ConfigErrorKind::UnexpectedToken,
+                "`x, y` or `x, y, label`",
+            ));
+        }
+
+        match segments.last_mut() {
+            Some(segment) if last_label == Some(label) => segment.push(point),
+            _ => segments.push(vec![point]),
+        }
+        last_label = Some(label);
+    }
+
+    if segments.is_empty() {
+        return Err(ConfigError {
+            kind: ConfigErrorKind::NoSegments,
+            line: csv.lines().count().max(1),
+            column: 1,
+            found: "".to_string(),
+            expected: "at least one row of `x, y`",
+        });
+    }
+
+    Ok(segments)
+}
+
+/// Splits a row on commas, returning each trimmed field along with the 1-based column it starts at.
+fn fields(line: &str) -> Vec<(usize, &str)> {
+    let mut fields = vec![];
+    let mut column = 1;
+    for field in line.split(',') {
+        let leading = field.len() - field.trim_start().len();
+        let start = column + field[..leading].chars().count();
+        fields.push((start, field.trim()));
+        column += field.chars().count() + 1;
+    }
+    fields
+}
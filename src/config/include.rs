@@ -0,0 +1,186 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::{config::Config, error::SledError};
+
+#[cfg(feature = "std")]
+use crate::error::{ConfigError, ConfigErrorKind};
+#[cfg(feature = "std")]
+use alloc::string::ToString as _;
+use glam::Affine2;
+
+#[cfg(feature = "std")]
+use crate::config::is_valid_name;
+#[cfg(feature = "std")]
+use std::collections::BTreeSet;
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "std")]
+use std::vec::Vec;
+
+/// An `include:` line, placing another layout file within this one.
+#[derive(Debug, Clone)]
+pub(crate) struct Include {
+    pub path: String,
+    /// The group the included chains are put in. Defaults to the file's name, without its extension.
+    pub name: Option<String>,
+    pub transform: Affine2,
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Reads each included file (relative to `base`, if given) and merges its chains into `config`, in order.
+///
+/// `stack` holds the files currently being included, to catch files that include themselves.
+#[cfg(feature = "std")]
+pub(crate) fn resolve(
+    mut config: Config,
+    includes: Vec<Include>,
+    base: Option<&Path>,
+    stack: &mut Vec<PathBuf>,
+) -> Result<Config, SledError> {
+    let mut groups = BTreeSet::new();
+    for include in includes {
+        let path = match base {
+            Some(base) => base.join(&include.path),
+            None => PathBuf::from(&include.path),
+        };
+        let at_include = |kind: ConfigErrorKind, found: &str, expected: &'static str| {
+            SledError::from_config_error(ConfigError {
+                kind,
+                line: include.line,
+                column: include.column,
+                found: found.to_string(),
+                expected,
+            })
+        };
+        let unreadable = |_| {
+            at_include(
+                ConfigErrorKind::InvalidInclude,
+                &include.path,
+                "the path of a readable layout file",
+            )
+        };
+
+        let canonical = path.canonicalize().map_err(unreadable)?;
+        if stack.contains(&canonical) {
+            return Err(at_include(
+                ConfigErrorKind::CyclicInclude,
+                &include.path,
+                "a file that isn't already being included",
+            ));
+        }
+        let contents = std::fs::read_to_string(&canonical).map_err(unreadable)?;
+
+        let group = match include.name {
+            Some(name) => name,
+            None => path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+        };
+        if !is_valid_name(&group) {
+            return Err(at_include(
+                ConfigErrorKind::InvalidInclude,
+                &group,
+                "a valid group name; set one with `[name: ...]`",
+            ));
+        }
+        if !groups.insert(group.clone()) {
+            return Err(at_include(
+                ConfigErrorKind::DuplicateName,
+                &group,
+                "a group name used by only one include; give each include its own `name`",
+            ));
+        }
+
+        stack.push(canonical);
+        let included = Config::from_str_in(&contents, path.parent(), stack)
+            .map_err(|e| e.in_file(&include.path))?;
+        stack.pop();
+
+        merge(&mut config, included, &group, include.transform)?;
+    }
+
+    if config.line_segments.is_empty() {
+        return SledError::new("A Sled needs at least one line segment.".into()).as_err();
+    }
+
+    if let Some(name) = first_duplicate_name(&config) {
+        return SledError::new(format!(
+            "The name `{}` is used more than once; each chain, segment and vertex needs a name of its own.",
+            name
+        ))
+        .as_err();
+    }
+
+    Ok(config)
+}
+
+/// Errors if there are any includes, as there's no way to read them without `std`.
+#[cfg(not(feature = "std"))]
+pub(crate) fn reject(config: Config, includes: &[Include]) -> Result<Config, SledError> {
+    match includes.first() {
+        Some(include) => SledError::new(format!(
+            "Can't include `{}` on line {}; reading files requires the `std` feature.",
+            include.path, include.line
+        ))
+        .as_err(),
+        None => Ok(config),
+    }
+}
+
+/// Appends `included`'s segments and chains to `config`, placed by `transform`.
+/// Every label is prefixed by `group/`, so identically named parts of different files stay distinct.
+#[cfg(feature = "std")]
+fn merge(
+    config: &mut Config,
+    included: Config,
+    group: &str,
+    transform: Affine2,
+) -> Result<(), SledError> {
+    let prefixed = |name: Option<String>| name.map(|name| format!("{}/{}", group, name));
+
+    let first_segment = config.line_segments.len();
+    for segment in &included.line_segments {
+        // includes only rotate, translate and uniformly scale, which every segment survives.
+        let mut segment = segment.transformed(transform).ok_or_else(|| {
+            SledError::new(format!(
+                "Couldn't place the layout included as `{}`.",
+                group
+            ))
+        })?;
+        segment.name = prefixed(segment.name.take());
+        segment.end_name = prefixed(segment.end_name.take());
+        config.line_segments.push(segment);
+    }
+
+    for mut chain in included.chains {
+        chain.segments = chain.segments.start + first_segment..chain.segments.end + first_segment;
+        chain.name = prefixed(chain.name.take());
+        chain.start_name = prefixed(chain.start_name.take());
+        chain.group = Some(prefixed(chain.group.take()).unwrap_or_else(|| group.into()));
+        config.chains.push(chain);
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "std")]
+fn first_duplicate_name(config: &Config) -> Option<&str> {
+    let chain_names = config
+        .chains
+        .iter()
+        .flat_map(|chain| [&chain.name, &chain.start_name]);
+    let segment_names = config
+        .line_segments
+        .iter()
+        .flat_map(|segment| [&segment.name, &segment.end_name]);
+
+    let mut seen = BTreeSet::new();
+    chain_names
+        .chain(segment_names)
+        .flatten()
+        .find(|name| !seen.insert(name.as_str()))
+        .map(String::as_str)
+}
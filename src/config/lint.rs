@@ -0,0 +1,208 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::{
+    config::{Config, LineSegment, SegmentKind},
+    Vec2, Vec3,
+};
+
+#[cfg(not(feature = "std"))]
+use num_traits::float::Float as _;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// How serious a [Lint] is.
+pub enum LintSeverity {
+    /// The layout can be built, but probably doesn't describe what was intended.
+    Warning,
+    /// The layout can't be built.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The different problems a layout can be linted for.
+pub enum LintKind {
+    /// A segment is too short for its density, so it has no LEDs. Its first and only entry in [Lint::segments] is that segment.
+    EmptySegment,
+    /// A segment starts and ends at the same point without curving, so all of its LEDs sit on top of each other.
+    ZeroLength,
+    /// Two segments trace over the same stretch of space, i.e. a segment was duplicated.
+    Overlapping,
+    /// The ends of two chains are closer together than the spacing between two LEDs, but don't touch.
+    /// Usually a typo in one of the vertices.
+    NearlyConnected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// A problem found in a layout. See [SledBuilder::lint()](crate::SledBuilder::lint).
+pub struct Lint {
+    pub kind: LintKind,
+    /// The segments involved, indexed as in [Sled::segment()](crate::Sled::segment).
+    pub segments: Vec<usize>,
+}
+
+impl Lint {
+    fn new(kind: LintKind, segments: Vec<usize>) -> Self {
+        Lint { kind, segments }
+    }
+
+    /// [LintKind::EmptySegment] is an error, everything else is a warning.
+    pub fn severity(&self) -> LintSeverity {
+        match self.kind {
+            LintKind::EmptySegment => LintSeverity::Error,
+            _ => LintSeverity::Warning,
+        }
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match (self.kind, self.segments.as_slice()) {
+            (LintKind::EmptySegment, [segment, ..]) => write!(
+                f,
+                "Segment {} has no LEDs; it is too short for its density.",
+                segment
+            ),
+            (LintKind::ZeroLength, [segment, ..]) => write!(
+                f,
+                "Segment {} starts and ends at the same point, so its LEDs overlap.",
+                segment
+            ),
+            (LintKind::Overlapping, [a, b, ..]) => {
+                write!(f, "Segments {} and {} overlap.", a, b)
+            }
+            (LintKind::NearlyConnected, [a, b, ..]) => write!(
+                f,
+                "Segments {} and {} almost meet, but don't share an endpoint.",
+                a, b
+            ),
+            (kind, segments) => write!(f, "{:?} in segments {:?}.", kind, segments),
+        }
+    }
+}
+
+impl Config {
+    /// Checks the layout for common mistakes, in order of segment index.
+    pub fn lint(&self) -> Vec<Lint> {
+        let mut lints = self.lint_errors();
+
+        // anything closer than the wider of two segments' LED spacings is close enough for their LEDs to collide.
+        let tolerance = |a: &LineSegment, b: &LineSegment| a.led_spacing().max(b.led_spacing());
+        for (index, segment) in self.line_segments.iter().enumerate() {
+            if segment.num_leds() > 0 && is_zero_length(segment) {
+                lints.push(Lint::new(LintKind::ZeroLength, vec![index]));
+            }
+
+            for (other_index, other) in self.line_segments.iter().enumerate().skip(index + 1) {
+                if overlaps(segment, other, tolerance(segment, other) * 0.5) {
+                    lints.push(Lint::new(LintKind::Overlapping, vec![index, other_index]));
+                }
+            }
+        }
+
+        // each chain's two loose ends, along with the segment they belong to.
+        let ends: Vec<(Vec3, usize)> = self
+            .chains
+            .iter()
+            .flat_map(|chain| {
+                let first = chain.segments.start;
+                let last = chain.segments.end - 1;
+                let (first_segment, last_segment) =
+                    (&self.line_segments[first], &self.line_segments[last]);
+                [
+                    (first_segment.start.extend(first_segment.start_z), first),
+                    (last_segment.end.extend(last_segment.end_z), last),
+                ]
+            })
+            .collect();
+
+        for (i, (end, segment)) in ends.iter().enumerate() {
+            for (other_end, other_segment) in &ends[i + 1..] {
+                let distance = end.distance(*other_end);
+                let tolerance = tolerance(
+                    &self.line_segments[*segment],
+                    &self.line_segments[*other_segment],
+                );
+                if distance > 0.0 && distance < tolerance {
+                    lints.push(Lint::new(
+                        LintKind::NearlyConnected,
+                        vec![*segment, *other_segment],
+                    ));
+                }
+            }
+        }
+
+        lints.sort_by_key(|lint| lint.segments[0]);
+        lints
+    }
+
+    /// Returns only the lints that would stop the layout from being built.
+    pub fn lint_errors(&self) -> Vec<Lint> {
+        self.line_segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.num_leds() == 0)
+            .map(|(index, _)| Lint::new(LintKind::EmptySegment, vec![index]))
+            .collect()
+    }
+}
+
+fn is_zero_length(segment: &LineSegment) -> bool {
+    segment.kind == SegmentKind::Line
+        && segment.start == segment.end
+        && segment.start_z == segment.end_z
+}
+
+/// Returns true if both segments are the same shape, in either direction,
+/// or are straight lines lying along each other for longer than `tolerance`.
+fn overlaps(a: &LineSegment, b: &LineSegment, tolerance: f32) -> bool {
+    // segments stacked at different heights don't collide, however they look from above.
+    if !same_heights(a, b, tolerance) {
+        return false;
+    }
+
+    let same_ends = a.start == b.start && a.end == b.end;
+    let reversed_ends = a.start == b.end && a.end == b.start;
+
+    match (&a.kind, &b.kind) {
+        (SegmentKind::Line, SegmentKind::Line) => lines_overlap(a, b, tolerance),
+        (SegmentKind::Arc(x), SegmentKind::Arc(y))
+        | (SegmentKind::QuadraticBezier(x), SegmentKind::QuadraticBezier(y)) => {
+            x == y && (same_ends || reversed_ends)
+        }
+        (SegmentKind::CubicBezier(a1, a2), SegmentKind::CubicBezier(b1, b2)) => {
+            (same_ends && a1 == b1 && a2 == b2) || (reversed_ends && a1 == b2 && a2 == b1)
+        }
+        (SegmentKind::Points(x), SegmentKind::Points(y)) => {
+            (same_ends && x == y) || (reversed_ends && x.iter().eq(y.iter().rev()))
+        }
+        _ => false,
+    }
+}
+
+/// Returns true if both segments' ends are at the same heights, in either direction.
+fn same_heights(a: &LineSegment, b: &LineSegment, tolerance: f32) -> bool {
+    let close = |x: f32, y: f32| (x - y).abs() <= tolerance;
+    (close(a.start_z, b.start_z) && close(a.end_z, b.end_z))
+        || (close(a.start_z, b.end_z) && close(a.end_z, b.start_z))
+}
+
+fn lines_overlap(a: &LineSegment, b: &LineSegment, tolerance: f32) -> bool {
+    let length = a.start.distance(a.end);
+    if length <= tolerance {
+        return false;
+    }
+
+    // b has to lie along a's line...
+    let direction = (a.end - a.start) / length;
+    let off_line = |p: Vec2| direction.perp_dot(p - a.start).abs();
+    if off_line(b.start) > tolerance || off_line(b.end) > tolerance {
+        return false;
+    }
+
+    // ...and share more than a point with it.
+    let along = |p: Vec2| direction.dot(p - a.start);
+    let (b_start, b_end) = (along(b.start), along(b.end));
+    let shared = b_start.max(b_end).min(length) - b_start.min(b_end).max(0.0);
+    shared > tolerance
+}
@@ -0,0 +1,614 @@
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f32::consts::{PI, TAU};
+use core::ops::Range;
+
+use crate::error::SledError;
+use crate::Vec2;
+use glam::{Affine2, Mat2, Vec3};
+use smallvec::SmallVec;
+
+mod builder;
+mod csv;
+mod include;
+mod lint;
+mod parse;
+mod write;
+
+pub use builder::{Chain, Segment, SledBuilder};
+pub(crate) use csv::parse_points;
+pub use lint::{Lint, LintKind, LintSeverity};
+
+#[cfg(not(feature = "std"))]
+use num_traits::float::Float as _;
+
+pub(crate) struct Config {
+    pub center_point: Vec2,
+    /// The center's height, for 3D layouts. Planar layouts leave this at 0.
+    pub center_z: f32,
+    pub density: f32,
+    pub line_segments: Vec<LineSegment>,
+    pub chains: Vec<ChainConfig>,
+}
+
+impl Config {
+    /// Parses a config, resolving any includes relative to the current directory.
+    pub fn from_str(s: &str) -> Result<Self, SledError> {
+        #[cfg(feature = "std")]
+        return Config::from_str_in(s, None, &mut Vec::new());
+
+        #[cfg(not(feature = "std"))]
+        {
+            let (config, includes) = parse::parse(s).map_err(SledError::from_config_error)?;
+            include::reject(config, &includes)
+        }
+    }
+
+    /// Parses a config, resolving any includes relative to `base`. `stack` holds the files already being included.
+    #[cfg(feature = "std")]
+    fn from_str_in(
+        s: &str,
+        base: Option<&std::path::Path>,
+        stack: &mut Vec<std::path::PathBuf>,
+    ) -> Result<Self, SledError> {
+        let (config, includes) = parse::parse(s).map_err(SledError::from_config_error)?;
+        include::resolve(config, includes, base, stack)
+    }
+
+    pub fn to_string(
+        center_point: Vec3,
+        density: f32,
+        line_segments: &[LineSegment],
+        chains: &[ChainConfig],
+    ) -> String {
+        write::write(center_point, density, line_segments, chains)
+    }
+
+    #[cfg(feature = "std")]
+    pub fn from_toml_file(path: &str) -> Result<Self, SledError> {
+        let as_string = std::fs::read_to_string(path).map_err(SledError::from_error)?;
+        let path = std::path::Path::new(path);
+        let mut stack: Vec<_> = path.canonicalize().into_iter().collect();
+        Config::from_str_in(&as_string, path.parent(), &mut stack)
+    }
+}
+
+// Validation rules shared by the text parser and SledBuilder.
+
+pub(crate) fn is_valid_density(density: f32) -> bool {
+    density.is_finite() && density > 0.0
+}
+
+pub(crate) fn is_valid_led_count(leds: usize) -> bool {
+    leds > 0
+}
+
+pub(crate) fn is_valid_coordinate(coordinate: f32) -> bool {
+    coordinate.is_finite()
+}
+
+/// Characters allowed in attribute keys and words like output names.
+pub(crate) fn is_name_char(c: char) -> bool {
+    !c.is_whitespace() && !matches!(c, ',' | '[' | ']' | '(' | ')' | ':' | '#' | '|')
+}
+
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(is_name_char)
+}
+
+/// Tag keys can't contain `=`, since it separates them from their values.
+pub(crate) fn is_valid_tag_key(key: &str) -> bool {
+    is_valid_name(key) && !key.contains('=')
+}
+
+/// Arbitrary `key=value` facts attached to a segment, like `zone=bar`.
+pub(crate) type Tags = BTreeMap<String, String>;
+
+/// The value given to tags written without one, like a bare `diffused`.
+pub(crate) const BARE_TAG_VALUE: &str = "true";
+
+/// The output chains are assigned to until one is named.
+pub(crate) const DEFAULT_OUTPUT: &str = "default";
+
+/// The nominal density given to layouts built from measured points, which don't use it.
+pub(crate) const POINTS_DENSITY: f32 = 1.0;
+
+/// A run of connected segments, along with how the data line is wired through it.
+///
+/// Every segment belongs to exactly one chain, and chains cover the segments in order.
+#[derive(Debug, Clone)]
+pub(crate) struct ChainConfig {
+    pub segments: Range<usize>,
+    /// The data line enters at the chain's last vertex and runs backwards.
+    pub reversed: bool,
+    /// How many LEDs along the (possibly reversed) chain the data line enters at.
+    pub offset: usize,
+    /// The name of the output (data pin) driving this chain.
+    pub output: String,
+    /// The chain's own label, if it was given one.
+    pub name: Option<String>,
+    /// The label of the chain's first vertex. Every other vertex is labelled by the segment ending at it.
+    pub start_name: Option<String>,
+    /// The included file this chain came from, as a `/`-separated path of include names for nested includes.
+    pub group: Option<String>,
+}
+
+/// How the number of LEDs on a [LineSegment] is determined.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum LedCount {
+    /// LEDs per unit of length, rounded to the nearest whole LED.
+    Density(f32),
+    /// A fixed number of LEDs, regardless of length.
+    Exact(usize),
+}
+
+/// The shape a [LineSegment] takes between its start and end points.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub(crate) enum SegmentKind {
+    Line,
+    /// A circular arc passing through the given point.
+    /// If the segment's start and end are equal, this is a full circle.
+    Arc(Vec2),
+    QuadraticBezier(Vec2),
+    CubicBezier(Vec2, Vec2),
+    /// One LED at each of the given points, such as positions measured by a camera mapping tool.
+    /// Queries treat the segment as a path running from its start, through each point in turn, to its end.
+    Points(Vec<Vec2>),
+}
+
+impl SegmentKind {
+    /// Returns this shape with each of its points passed through `transform`.
+    pub fn transformed(&self, transform: Affine2) -> SegmentKind {
+        let t = |point: Vec2| transform.transform_point2(point);
+        match self {
+            SegmentKind::Line => SegmentKind::Line,
+            SegmentKind::Arc(through) => SegmentKind::Arc(t(*through)),
+            SegmentKind::QuadraticBezier(control) => SegmentKind::QuadraticBezier(t(*control)),
+            SegmentKind::CubicBezier(control_1, control_2) => {
+                SegmentKind::CubicBezier(t(*control_1), t(*control_2))
+            }
+            SegmentKind::Points(points) => {
+                SegmentKind::Points(points.iter().map(|point| t(*point)).collect())
+            }
+        }
+    }
+}
+
+// resolution used when flattening curves into straight pieces
+const BEZIER_STEPS: usize = 64;
+const ARC_STEP: f32 = PI / 90.0;
+
+/// Which of a segment's LED slots are gaps: wired into the data line, but hidden or dead.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(rename_all = "snake_case")
+)]
+pub(crate) enum Gaps {
+    #[default]
+    None,
+    /// Every LED on the segment.
+    All,
+    /// Runs of LEDs, counted from the segment's start.
+    Runs(Vec<Range<usize>>),
+}
+
+impl Gaps {
+    pub fn contains(&self, led: usize) -> bool {
+        match self {
+            Gaps::None => false,
+            Gaps::All => true,
+            Gaps::Runs(runs) => runs.iter().any(|run| run.contains(&led)),
+        }
+    }
+
+    /// Returns the index of the first run that is empty or extends past the segment's last LED.
+    pub fn first_invalid_run(&self, num_leds: usize) -> Option<usize> {
+        match self {
+            Gaps::Runs(runs) => runs
+                .iter()
+                .position(|run| run.start >= run.end || run.end > num_leds),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct LineSegment {
+    pub start: Vec2,
+    pub end: Vec2,
+    pub kind: SegmentKind,
+    pub led_count: LedCount,
+    pub gaps: Gaps,
+    pub name: Option<String>,
+    /// The label of the vertex this segment ends at.
+    pub end_name: Option<String>,
+    pub tags: Tags,
+    /// The heights of the segment's start and end, for 3D layouts. LEDs rise or fall linearly between them.
+    pub start_z: f32,
+    pub end_z: f32,
+    // Curves are approximated by a polyline so that positions and queries can
+    // be parameterized by arc length. Both are empty for straight lines.
+    polyline: Vec<Vec2>,
+    // how far along the segment each point in the polyline is, from 0 to 1.
+    alphas: Vec<f32>,
+    length: f32,
+}
+
+impl LineSegment {
+    /// Returns `None` if the described curve is degenerate, i.e. an arc through three collinear points,
+    /// or an explicit list of points is empty.
+    pub fn new(start: Vec2, end: Vec2, kind: SegmentKind, led_count: LedCount) -> Option<Self> {
+        let mut polyline: Vec<Vec2> = match &kind {
+            SegmentKind::Line => vec![],
+            SegmentKind::Arc(through) => arc_polyline(start, *through, end)?,
+            SegmentKind::QuadraticBezier(control) => (0..=BEZIER_STEPS)
+                .map(|i| {
+                    let t = i as f32 / BEZIER_STEPS as f32;
+                    let a = start.lerp(*control, t);
+                    let b = control.lerp(end, t);
+                    a.lerp(b, t)
+                })
+                .collect(),
+            SegmentKind::CubicBezier(control_1, control_2) => (0..=BEZIER_STEPS)
+                .map(|i| {
+                    let t = i as f32 / BEZIER_STEPS as f32;
+                    let a = start.lerp(*control_1, t);
+                    let b = control_1.lerp(*control_2, t);
+                    let c = control_2.lerp(end, t);
+                    a.lerp(b, t).lerp(b.lerp(c, t), t)
+                })
+                .collect(),
+            SegmentKind::Points(points) if points.is_empty() => return None,
+            SegmentKind::Points(points) => core::iter::once(start)
+                .chain(points.iter().copied())
+                .chain(core::iter::once(end))
+                .collect(),
+        };
+
+        // make sure the endpoints are exact, regardless of rounding along the way.
+        if let [first, .., last] = polyline.as_mut_slice() {
+            *first = start;
+            *last = end;
+        }
+
+        let mut arc_lengths = Vec::with_capacity(polyline.len());
+        let mut total = 0.0;
+        for (i, point) in polyline.iter().enumerate() {
+            if i > 0 {
+                total += polyline[i - 1].distance(*point);
+            }
+            arc_lengths.push(total);
+        }
+
+        let alphas = match &kind {
+            // each point is centered on the stretch of alpha that maps to its LED,
+            // so that queries landing on or near a point resolve to that point's LED.
+            SegmentKind::Points(points) => {
+                let num_points = points.len() as f32;
+                core::iter::once(0.0)
+                    .chain((0..points.len()).map(|i| (i as f32 + 0.5) / num_points))
+                    .chain(core::iter::once(1.0))
+                    .collect()
+            }
+            _ => {
+                let inv_total = if total > 0.0 { 1.0 / total } else { 0.0 };
+                arc_lengths
+                    .iter()
+                    .map(|length| length * inv_total)
+                    .collect()
+            }
+        };
+
+        let length = match kind {
+            SegmentKind::Line => start.distance(end),
+            _ => total,
+        };
+
+        Some(LineSegment {
+            start,
+            end,
+            kind,
+            led_count,
+            gaps: Gaps::None,
+            name: None,
+            end_name: None,
+            tags: Tags::new(),
+            start_z: 0.0,
+            end_z: 0.0,
+            polyline,
+            alphas,
+            length,
+        })
+    }
+
+    pub fn num_leds(&self) -> usize {
+        match self.led_count {
+            LedCount::Density(density) => (self.length() * density).round() as usize,
+            LedCount::Exact(leds) => leds,
+        }
+    }
+
+    /// The distance between neighbouring LEDs along the segment. Segments without any LEDs report their full length.
+    pub fn led_spacing(&self) -> f32 {
+        match self.num_leds() {
+            0 => self.length(),
+            leds => self.length() / leds as f32,
+        }
+    }
+
+    pub fn is_curved(&self) -> bool {
+        self.kind != SegmentKind::Line
+    }
+
+    /// The segment's length, including any climb between `start_z` and `end_z`.
+    pub fn length(&self) -> f32 {
+        let climb = self.end_z - self.start_z;
+        if climb == 0.0 {
+            self.length
+        } else {
+            // heights change linearly with arc length, so the path is a curve of constant slope.
+            (self.length * self.length + climb * climb).sqrt()
+        }
+    }
+
+    /// Returns the position of the `led`th LED along the segment.
+    pub fn led_position(&self, led: usize) -> Vec2 {
+        match &self.kind {
+            SegmentKind::Points(points) => points[led],
+            _ => self.point_at((led + 1) as f32 / self.num_leds() as f32),
+        }
+    }
+
+    /// Returns the height of the `led`th LED along the segment, interpolated between `start_z` and `end_z`
+    /// by how far along the segment the LED sits.
+    #[cfg(feature = "3d")]
+    pub fn led_z(&self, led: usize) -> f32 {
+        let alpha = match &self.kind {
+            SegmentKind::Points(points) => (led as f32 + 0.5) / points.len() as f32,
+            _ => (led + 1) as f32 / self.num_leds() as f32,
+        };
+        self.start_z + (self.end_z - self.start_z) * alpha
+    }
+
+    /// Returns the point `alpha` of the way along the segment, measured by arc length.
+    pub fn point_at(&self, alpha: f32) -> Vec2 {
+        if !self.is_curved() {
+            return self.start.lerp(self.end, alpha);
+        }
+
+        let i = self
+            .alphas
+            .partition_point(|a| *a < alpha)
+            .clamp(1, self.polyline.len() - 1);
+
+        let (a0, a1) = (self.alphas[i - 1], self.alphas[i]);
+        let t = if a1 > a0 {
+            (alpha - a0) / (a1 - a0)
+        } else {
+            0.0
+        };
+        self.polyline[i - 1].lerp(self.polyline[i], t)
+    }
+
+    /// Iterates over the straight pieces that make up this segment, along with the alpha values at either end of each piece.
+    fn pieces(&self) -> impl Iterator<Item = (Vec2, Vec2, f32, f32)> + '_ {
+        let line = (!self.is_curved()).then_some((self.start, self.end, 0.0, 1.0));
+        let curve = self
+            .polyline
+            .windows(2)
+            .zip(self.alphas.windows(2))
+            .map(|(p, a)| (p[0], p[1], a[0], a[1]));
+
+        line.into_iter().chain(curve)
+    }
+
+    fn num_pieces(&self) -> usize {
+        self.polyline.len().saturating_sub(1).max(1)
+    }
+
+    pub fn intersects_line(&self, other_start: Vec2, other_end: Vec2) -> SmallVec<[f32; 2]> {
+        let last_piece = self.num_pieces() - 1;
+        let mut return_values = smallvec::smallvec![];
+
+        for (i, (start, end, alpha_0, alpha_1)) in self.pieces().enumerate() {
+            if let Some(t) = line_intersects_line(start, end, other_start, other_end) {
+                // pieces share endpoints, so only count a hit at the very end of a piece once.
+                if t < 1.0 || i == last_piece {
+                    return_values.push(alpha_0 + (alpha_1 - alpha_0) * t);
+                }
+            }
+        }
+
+        return_values
+    }
+
+    pub fn intersects_circle(&self, circle_center: Vec2, circle_radius: f32) -> SmallVec<[f32; 2]> {
+        let last_piece = self.num_pieces() - 1;
+        let mut return_values = smallvec::smallvec![];
+
+        for (i, (start, end, alpha_0, alpha_1)) in self.pieces().enumerate() {
+            for t in line_intersects_circle(start, end, circle_center, circle_radius) {
+                if t < 1.0 || i == last_piece {
+                    return_values.push(alpha_0 + (alpha_1 - alpha_0) * t);
+                }
+            }
+        }
+
+        return_values
+    }
+
+    /// Returns this segment with every point passed through `transform`, keeping its LED count, gaps, labels and tags.
+    ///
+    /// Returns `None` if the segment is an arc and `transform` doesn't preserve angles,
+    /// since the result would be an ellipse rather than a circle.
+    pub fn transformed(&self, transform: Affine2) -> Option<Self> {
+        if matches!(self.kind, SegmentKind::Arc(_)) && !is_similarity(transform.matrix2) {
+            return None;
+        }
+
+        let start = transform.transform_point2(self.start);
+        let end = transform.transform_point2(self.end);
+        let kind = self.kind.transformed(transform);
+
+        // scale the density along with the layout where possible, so LED counts are unchanged.
+        let scale = transform.matrix2.determinant().abs().sqrt();
+        // heights are perpendicular to the plane, so they only follow its scale.
+        let with_heights = |mut segment: LineSegment| {
+            segment.start_z = self.start_z * scale;
+            segment.end_z = self.end_z * scale;
+            segment
+        };
+        let num_leds = self.num_leds();
+        let scaled = match self.led_count {
+            LedCount::Density(density) => {
+                LineSegment::new(start, end, kind.clone(), LedCount::Density(density / scale))
+                    .map(with_heights)
+                    .filter(|scaled| scaled.num_leds() == num_leds)
+            }
+            LedCount::Exact(_) => None,
+        };
+
+        let mut segment = match scaled {
+            Some(scaled) => scaled,
+            None => {
+                LineSegment::new(start, end, kind, LedCount::Exact(num_leds)).map(with_heights)?
+            }
+        };
+        segment.gaps = self.gaps.clone();
+        segment.name = self.name.clone();
+        segment.end_name = self.end_name.clone();
+        segment.tags = self.tags.clone();
+        Some(segment)
+    }
+}
+
+/// Returns true if `matrix` only rotates, reflects and uniformly scales, i.e. it maps circles to circles.
+fn is_similarity(matrix: Mat2) -> bool {
+    let (x, y) = (matrix.x_axis, matrix.y_axis);
+    let tolerance = 1e-5 * x.length_squared().max(y.length_squared());
+    x.dot(y).abs() <= tolerance && (x.length_squared() - y.length_squared()).abs() <= tolerance
+}
+
+fn line_intersects_line(start: Vec2, end: Vec2, other_start: Vec2, other_end: Vec2) -> Option<f32> {
+    let s1 = end - start;
+    let s2 = other_end - other_start;
+    let start_dif = start - other_start;
+
+    let denom = s1.x * s2.y - s2.x * s1.y;
+
+    // check if parallel
+    if denom.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let inv_denom = 1.0 / denom;
+    let s = (-s1.y * start_dif.x + s1.x * start_dif.y) * inv_denom;
+    let t = (s2.x * start_dif.y - s2.y * start_dif.x) * inv_denom;
+
+    if (0.0..=1.0).contains(&s) && (0.0..=1.0).contains(&t) {
+        Some(t)
+    } else {
+        None
+    }
+}
+
+fn line_intersects_circle(
+    start: Vec2,
+    end: Vec2,
+    circle_center: Vec2,
+    circle_radius: f32,
+) -> SmallVec<[f32; 2]> {
+    let v1 = end - start;
+    let v2 = start - circle_center;
+
+    let b = -2.0 * v1.dot(v2);
+    let c = 2.0 * v1.length_squared();
+    let mut return_values = smallvec::smallvec![];
+
+    let mut d = b * b - 2.0 * c * (v2.length_squared() - circle_radius.powi(2));
+    if d < 0.0 {
+        return return_values;
+    }
+
+    d = d.sqrt();
+
+    let t1 = (b - d) / c;
+    let t2 = (b + d) / c;
+
+    if (0.0..=1.0).contains(&t1) {
+        return_values.push(t1);
+    }
+    if (0.0..=1.0).contains(&t2) {
+        return_values.push(t2);
+    }
+
+    return_values
+}
+
+/// Approximates the circular arc from `start` to `end` passing through `through`.
+/// Returns `None` if no such arc exists.
+fn arc_polyline(start: Vec2, through: Vec2, end: Vec2) -> Option<Vec<Vec2>> {
+    let angle_from = |center: Vec2, point: Vec2| {
+        let offset = point - center;
+        offset.y.atan2(offset.x)
+    };
+
+    let (center, start_angle, sweep) = if start == end {
+        // full circle, with `through` on the opposite side from `start`.
+        if through == start {
+            return None;
+        }
+        let center = (start + through) * 0.5;
+        (center, angle_from(center, start), TAU)
+    } else {
+        let center = circumcenter(start, through, end)?;
+        let start_angle = angle_from(center, start);
+        let wrap = |angle: f32| if angle < 0.0 { angle + TAU } else { angle };
+        let to_end = wrap(angle_from(center, end) - start_angle);
+        let to_through = wrap(angle_from(center, through) - start_angle);
+
+        // sweep counter-clockwise if that passes through `through`, clockwise otherwise.
+        let sweep = if to_through < to_end {
+            to_end
+        } else {
+            to_end - TAU
+        };
+        (center, start_angle, sweep)
+    };
+
+    let radius = start.distance(center);
+    let steps = ((sweep.abs() / ARC_STEP).ceil() as usize).max(2);
+    let points = (0..=steps)
+        .map(|i| {
+            let angle = start_angle + sweep * (i as f32 / steps as f32);
+            center + Vec2::from_angle(angle) * radius
+        })
+        .collect();
+
+    Some(points)
+}
+
+fn circumcenter(a: Vec2, b: Vec2, c: Vec2) -> Option<Vec2> {
+    let ab = b - a;
+    let ac = c - a;
+    let cross = ab.perp_dot(ac);
+    if cross.abs() <= 1e-6 * ab.length() * ac.length() {
+        return None;
+    }
+
+    let d = 2.0 * cross;
+    let ab_sq = ab.length_squared();
+    let ac_sq = ac.length_squared();
+    let offset = Vec2::new(ac.y * ab_sq - ab.y * ac_sq, ab.x * ac_sq - ac.x * ab_sq) / d;
+
+    Some(a + offset)
+}
@@ -0,0 +1,1166 @@
+use alloc::collections::BTreeSet;
+use alloc::string::{String, ToString as _};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::config::{
+    include::Include, is_name_char, is_valid_coordinate, is_valid_density, is_valid_led_count,
+    ChainConfig, Config, Gaps, LedCount, LineSegment, SegmentKind, Tags, BARE_TAG_VALUE,
+    DEFAULT_OUTPUT,
+};
+use crate::error::{ConfigError, ConfigErrorKind};
+use crate::Vec2;
+use glam::{Affine2, Vec3};
+use smallvec::SmallVec;
+
+const SEGMENT_MARKER: &str = "--segments--";
+
+/// Walks over a section of the config one character at a time, keeping
+/// track of line and column so errors can point at the offending text.
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+    line: usize,
+    column: usize,
+}
+
+enum Token<'a> {
+    Vertex(Vec3),
+    Arrow,
+    Break,
+    Attributes(SmallVec<[Attribute<'a>; 2]>),
+}
+
+/// A `key: value` pair (or bare `key` flag) from an attribute list like `[density: 60, leds: 47]`.
+struct Attribute<'a> {
+    key: &'a str,
+    values: SmallVec<[AttributeValue<'a>; 1]>,
+    line: usize,
+    column: usize,
+}
+
+struct AttributeValue<'a> {
+    vertex: Option<Vec2>,
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(src: &'a str, line: usize, column: usize) -> Self {
+        Lexer {
+            src,
+            pos: 0,
+            line,
+            column,
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// Skips whitespace and `#` comments, which run until the end of the line.
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while self.peek().is_some_and(|c| c != '\n') {
+                    self.bump();
+                }
+            } else if c.is_whitespace() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Returns the run of non-whitespace characters at the cursor, for use in error messages.
+    fn word_at_cursor(&self) -> &'a str {
+        let rest = &self.src[self.pos..];
+        let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        &rest[..end]
+    }
+
+    /// Consumes an attribute key or value, stopping at whitespace or punctuation.
+    fn word(&mut self) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(is_name_char) {
+            self.bump();
+        }
+        &self.src[start..self.pos]
+    }
+
+    fn error(&self, kind: ConfigErrorKind, found: &str, expected: &'static str) -> ConfigError {
+        ConfigError {
+            kind,
+            line: self.line,
+            column: self.column,
+            found: found.to_string(),
+            expected,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token<'a>>, ConfigError> {
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(None),
+            Some('(') => Ok(Some(Token::Vertex(self.vertex_3d()?))),
+            Some('[') => Ok(Some(Token::Attributes(self.attributes()?))),
+            Some('|') => {
+                self.bump();
+                Ok(Some(Token::Break))
+            }
+            Some('-') if self.src[self.pos..].starts_with("-->") => {
+                for _ in 0..3 {
+                    self.bump();
+                }
+                Ok(Some(Token::Arrow))
+            }
+            Some(_) => Err(self.error(
+                ConfigErrorKind::UnexpectedToken,
+                self.word_at_cursor(),
+                "a vertex, `-->`, `|` or `[`",
+            )),
+        }
+    }
+
+    /// Parses a vertex of the form `(x, y)`. The cursor must be on the opening parenthesis.
+    fn vertex(&mut self) -> Result<Vec2, ConfigError> {
+        self.components(false).map(Vec3::truncate)
+    }
+
+    /// Parses a vertex of the form `(x, y)` or `(x, y, z)`, where `z` defaults to 0. The cursor must be on the opening parenthesis.
+    fn vertex_3d(&mut self) -> Result<Vec3, ConfigError> {
+        self.components(true)
+    }
+
+    fn components(&mut self, allow_z: bool) -> Result<Vec3, ConfigError> {
+        let (line, column) = (self.line, self.column);
+        let start = self.pos;
+        self.bump();
+
+        let mut components: SmallVec<[f32; 3]> = SmallVec::new();
+        loop {
+            self.skip_whitespace();
+            let component_start = self.pos;
+            let (component_line, component_column) = (self.line, self.column);
+
+            while self
+                .peek()
+                .is_some_and(|c| !matches!(c, ',' | ')' | '(' | '#') && !c.is_whitespace())
+            {
+                self.bump();
+            }
+
+            let text = &self.src[component_start..self.pos];
+            let value: f32 = text
+                .parse()
+                .ok()
+                .filter(|v: &f32| is_valid_coordinate(*v))
+                .ok_or_else(|| ConfigError {
+                    kind: ConfigErrorKind::InvalidNumber,
+                    line: component_line,
+                    column: component_column,
+                    found: text.to_string(),
+                    expected: "a number",
+                })?;
+            components.push(value);
+
+            self.skip_whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(')') => break,
+                Some(_) => {
+                    return Err(ConfigError {
+                        kind: ConfigErrorKind::MalformedVec2,
+                        line,
+                        column,
+                        found: self.src[start..self.pos].to_string(),
+                        expected: "a closing `)`",
+                    })
+                }
+                None => {
+                    return Err(self.error(ConfigErrorKind::UnexpectedEnd, "", "a closing `)`"))
+                }
+            }
+        }
+
+        match components.as_slice() {
+            [x, y] => Ok(Vec3::new(*x, *y, 0.0)),
+            [x, y, z] if allow_z => Ok(Vec3::new(*x, *y, *z)),
+            _ => Err(ConfigError {
+                kind: ConfigErrorKind::MalformedVec2,
+                line,
+                column,
+                found: self.src[start..self.pos].to_string(),
+                expected: if allow_z {
+                    "a vertex of the form `(x, y)` or `(x, y, z)`"
+                } else {
+                    "a vertex of the form `(x, y)`"
+                },
+            }),
+        }
+    }
+
+    /// Parses an attribute list of the form `[key: value, flag, ...]`. The cursor must be on the opening bracket.
+    fn attributes(&mut self) -> Result<SmallVec<[Attribute<'a>; 2]>, ConfigError> {
+        self.bump();
+        let mut attributes = SmallVec::new();
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(attributes);
+            }
+
+            let (line, column) = (self.line, self.column);
+            let key = self.word();
+            if key.is_empty() {
+                return Err(match self.peek() {
+                    Some(_) => self.error(
+                        ConfigErrorKind::UnexpectedToken,
+                        self.word_at_cursor(),
+                        "an attribute name",
+                    ),
+                    None => self.error(ConfigErrorKind::UnexpectedEnd, "", "a closing `]`"),
+                });
+            }
+
+            let mut values = SmallVec::new();
+            self.skip_whitespace();
+            if self.peek() == Some(':') {
+                self.bump();
+                loop {
+                    self.skip_whitespace();
+                    let (value_line, value_column) = (self.line, self.column);
+                    let value_start = self.pos;
+                    let vertex = match self.peek() {
+                        Some(',' | ']') => break,
+                        Some('(') => Some(self.vertex()?),
+                        Some(_) => {
+                            if self.word().is_empty() {
+                                return Err(self.error(
+                                    ConfigErrorKind::UnexpectedToken,
+                                    self.word_at_cursor(),
+                                    "an attribute value",
+                                ));
+                            }
+                            None
+                        }
+                        None => {
+                            return Err(self.error(
+                                ConfigErrorKind::UnexpectedEnd,
+                                "",
+                                "a closing `]`",
+                            ))
+                        }
+                    };
+
+                    values.push(AttributeValue {
+                        vertex,
+                        text: &self.src[value_start..self.pos],
+                        line: value_line,
+                        column: value_column,
+                    });
+                }
+
+                if values.is_empty() {
+                    return Err(self.error(
+                        ConfigErrorKind::UnexpectedToken,
+                        self.word_at_cursor(),
+                        "an attribute value",
+                    ));
+                }
+            }
+
+            attributes.push(Attribute {
+                key,
+                values,
+                line,
+                column,
+            });
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(',') => {
+                    self.bump();
+                }
+                Some(']') => {}
+                Some(_) => {
+                    return Err(self.error(
+                        ConfigErrorKind::UnexpectedToken,
+                        self.word_at_cursor(),
+                        "`,` or `]`",
+                    ))
+                }
+                None => {
+                    return Err(self.error(ConfigErrorKind::UnexpectedEnd, "", "a closing `]`"))
+                }
+            }
+        }
+    }
+
+    /// Errors if anything other than whitespace remains.
+    fn expect_end(&mut self) -> Result<(), ConfigError> {
+        self.skip_whitespace();
+        if self.peek().is_some() {
+            return Err(self.error(
+                ConfigErrorKind::UnexpectedToken,
+                self.word_at_cursor(),
+                "end of line",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Attribute<'_> {
+    fn error(&self, kind: ConfigErrorKind, expected: &'static str) -> ConfigError {
+        ConfigError {
+            kind,
+            line: self.line,
+            column: self.column,
+            found: self.key.to_string(),
+            expected,
+        }
+    }
+
+    /// Returns the attribute's value, erroring if it was given none or more than one.
+    fn single_value(&self, expected: &'static str) -> Result<&AttributeValue<'_>, ConfigError> {
+        match self.values.as_slice() {
+            [value] => Ok(value),
+            [] => Err(self.error(ConfigErrorKind::UnexpectedToken, expected)),
+            [_, extra, ..] => Err(extra.error(ConfigErrorKind::UnexpectedToken, "`,` or `]`")),
+        }
+    }
+
+    /// Returns the attribute's values as vertices, erroring if there are more than `max` of them or any are not vertices.
+    fn vertices(
+        &self,
+        max: usize,
+        expected: &'static str,
+    ) -> Result<SmallVec<[Vec2; 2]>, ConfigError> {
+        if self.values.is_empty() {
+            return Err(self.error(ConfigErrorKind::UnexpectedToken, expected));
+        }
+        if let Some(extra) = self.values.get(max) {
+            return Err(extra.error(ConfigErrorKind::UnexpectedToken, "`,` or `]`"));
+        }
+
+        self.values
+            .iter()
+            .map(|value| {
+                value
+                    .vertex
+                    .ok_or_else(|| value.error(ConfigErrorKind::MalformedVec2, expected))
+            })
+            .collect()
+    }
+
+    fn density(&self) -> Result<f32, ConfigError> {
+        let value = self.single_value("a positive number")?;
+        value
+            .text
+            .parse()
+            .ok()
+            .filter(|n: &f32| is_valid_density(*n))
+            .ok_or_else(|| value.error(ConfigErrorKind::InvalidNumber, "a positive number"))
+    }
+
+    /// Errors if a bare flag like `reversed` was given a value.
+    fn flag(&self) -> Result<(), ConfigError> {
+        match self.values.first() {
+            Some(value) => Err(value.error(ConfigErrorKind::UnexpectedToken, "`,` or `]`")),
+            None => Ok(()),
+        }
+    }
+
+    fn offset(&self) -> Result<usize, ConfigError> {
+        let value = self.single_value("a whole number")?;
+        value
+            .text
+            .parse()
+            .map_err(|_| value.error(ConfigErrorKind::InvalidNumber, "a whole number"))
+    }
+
+    fn name(&self) -> Result<&str, ConfigError> {
+        let value = self.single_value("a name")?;
+        match value.vertex {
+            Some(_) => Err(value.error(ConfigErrorKind::UnexpectedToken, "a name")),
+            None => Ok(value.text),
+        }
+    }
+
+    /// Returns the attribute's value as a label, erroring if another chain, segment or vertex already took it.
+    fn label(&self, labels: &mut BTreeSet<String>) -> Result<String, ConfigError> {
+        let name = self.name()?;
+        if !labels.insert(name.to_string()) {
+            return Err(self.values[0].error(
+                ConfigErrorKind::DuplicateName,
+                "a name not used elsewhere in the layout",
+            ));
+        }
+        Ok(name.to_string())
+    }
+
+    /// Parses `tags: zone=bar diffused`, where a tag without a value is given the value `true`.
+    fn tags(&self) -> Result<Tags, ConfigError> {
+        const EXPECTED: &str = "one or more tags like `zone=bar` or `diffused`";
+        if self.values.is_empty() {
+            return Err(self.error(ConfigErrorKind::UnexpectedToken, EXPECTED));
+        }
+
+        let mut tags = Tags::new();
+        for value in &self.values {
+            let (key, tag_value) = match value.text.split_once('=') {
+                _ if value.vertex.is_some() => ("", ""),
+                Some((key, tag_value)) => (key, tag_value),
+                None => (value.text, BARE_TAG_VALUE),
+            };
+            if key.is_empty() || tag_value.is_empty() {
+                return Err(value.error(ConfigErrorKind::UnexpectedToken, EXPECTED));
+            }
+            if tags
+                .insert(key.to_string(), tag_value.to_string())
+                .is_some()
+            {
+                return Err(value.error(ConfigErrorKind::DuplicateKey, "each tag at most once"));
+            }
+        }
+        Ok(tags)
+    }
+
+    /// Parses a bare `gap` (the whole segment) or `gap: a..b c` (runs of LEDs, end exclusive).
+    fn gaps(&self) -> Result<Gaps, ConfigError> {
+        if self.values.is_empty() {
+            return Ok(Gaps::All);
+        }
+
+        self.values
+            .iter()
+            .map(|value| value.led_run())
+            .collect::<Result<_, _>>()
+            .map(Gaps::Runs)
+    }
+
+    fn led_count(&self) -> Result<usize, ConfigError> {
+        let value = self.single_value("a positive whole number")?;
+        value
+            .text
+            .parse()
+            .ok()
+            .filter(|n: &usize| is_valid_led_count(*n))
+            .ok_or_else(|| value.error(ConfigErrorKind::InvalidNumber, "a positive whole number"))
+    }
+}
+
+impl AttributeValue<'_> {
+    /// Parses a single LED index like `3`, or a range of them like `3..7`.
+    fn led_run(&self) -> Result<Range<usize>, ConfigError> {
+        let index = |text: &str| text.parse::<usize>().ok();
+        let run = match self.text.split_once("..") {
+            Some((start, end)) => index(start).zip(index(end)).map(|(start, end)| start..end),
+            None => index(self.text).map(|i| i..i + 1),
+        };
+
+        run.filter(|run| run.start < run.end).ok_or_else(|| {
+            self.error(
+                ConfigErrorKind::InvalidNumber,
+                "an LED index like `3` or range like `3..7`",
+            )
+        })
+    }
+
+    fn error(&self, kind: ConfigErrorKind, expected: &'static str) -> ConfigError {
+        ConfigError {
+            kind,
+            line: self.line,
+            column: self.column,
+            found: self.text.to_string(),
+            expected,
+        }
+    }
+}
+
+/// Options declared in a `[...]` list at the start of a chain.
+#[derive(Default)]
+struct ChainOptions {
+    density: Option<f32>,
+    reversed: bool,
+    offset: Option<usize>,
+    output: Option<String>,
+    name: Option<String>,
+    tags: Tags,
+    group: Option<String>,
+}
+
+/// Options declared in a `[...]` list right after a `-->`.
+#[derive(Default)]
+struct SegmentOptions {
+    density: Option<f32>,
+    leds: Option<usize>,
+    // the curve's shape, along with the line and column it was declared at.
+    shape: Option<(SegmentKind, usize, usize)>,
+    gaps: Option<Gaps>,
+    name: Option<String>,
+    tags: Option<Tags>,
+    // one per gap run, reported if that run turns out to extend past the segment's last LED.
+    gap_errors: Vec<ConfigError>,
+}
+
+fn chain_options(
+    attributes: &[Attribute],
+    labels: &mut BTreeSet<String>,
+) -> Result<ChainOptions, ConfigError> {
+    let mut options = ChainOptions::default();
+    for attribute in attributes {
+        match attribute.key {
+            "density" if options.density.is_none() => options.density = Some(attribute.density()?),
+            "reversed" if !options.reversed => {
+                attribute.flag()?;
+                options.reversed = true;
+            }
+            "offset" if options.offset.is_none() => options.offset = Some(attribute.offset()?),
+            "output" if options.output.is_none() => {
+                options.output = Some(attribute.name()?.to_string())
+            }
+            "name" if options.name.is_none() => options.name = Some(attribute.label(labels)?),
+            "tags" if options.tags.is_empty() => options.tags = attribute.tags()?,
+            "group" if options.group.is_none() => {
+                options.group = Some(attribute.name()?.to_string())
+            }
+            "density" | "reversed" | "offset" | "output" | "name" | "tags" | "group" => {
+                return Err(attribute.error(
+                    ConfigErrorKind::DuplicateKey,
+                    "each attribute at most once per chain",
+                ))
+            }
+            _ => {
+                return Err(attribute.error(
+                    ConfigErrorKind::UnknownKey,
+                    "`density`, `reversed`, `offset`, `output`, `name`, `tags` or `group`",
+                ))
+            }
+        }
+    }
+    Ok(options)
+}
+
+fn segment_options(
+    attributes: &[Attribute],
+    labels: &mut BTreeSet<String>,
+) -> Result<SegmentOptions, ConfigError> {
+    let mut options = SegmentOptions::default();
+    for attribute in attributes {
+        match attribute.key {
+            "density" | "leds" if options.density.is_some() || options.leds.is_some() => {
+                return Err(attribute.error(
+                    ConfigErrorKind::DuplicateKey,
+                    "a single `density` or `leds`",
+                ))
+            }
+            "density" => options.density = Some(attribute.density()?),
+            "leds" => options.leds = Some(attribute.led_count()?),
+            "name" if options.name.is_some() => {
+                return Err(attribute.error(ConfigErrorKind::DuplicateKey, "a single `name`"))
+            }
+            "name" => options.name = Some(attribute.label(labels)?),
+            "tags" if options.tags.is_some() => {
+                return Err(attribute.error(ConfigErrorKind::DuplicateKey, "a single `tags`"))
+            }
+            "tags" => options.tags = Some(attribute.tags()?),
+            "gap" if options.gaps.is_some() => {
+                return Err(attribute.error(ConfigErrorKind::DuplicateKey, "a single `gap`"))
+            }
+            "gap" => {
+                options.gaps = Some(attribute.gaps()?);
+                options.gap_errors = attribute
+                    .values
+                    .iter()
+                    .map(|value| {
+                        value.error(
+                            ConfigErrorKind::InvalidNumber,
+                            "LED indices within the segment",
+                        )
+                    })
+                    .collect();
+            }
+            "arc" | "bezier" | "points" if options.shape.is_some() => {
+                return Err(attribute.error(
+                    ConfigErrorKind::DuplicateKey,
+                    "a single `arc`, `bezier` or `points`",
+                ))
+            }
+            "arc" => {
+                let through = attribute.vertices(1, "a point the arc passes through")?;
+                options.shape = Some((
+                    SegmentKind::Arc(through[0]),
+                    attribute.line,
+                    attribute.column,
+                ));
+            }
+            "bezier" => {
+                let kind = match attribute
+                    .vertices(2, "one or two control points")?
+                    .as_slice()
+                {
+                    [control] => SegmentKind::QuadraticBezier(*control),
+                    [control_1, control_2] => SegmentKind::CubicBezier(*control_1, *control_2),
+                    _ => unreachable!(),
+                };
+                options.shape = Some((kind, attribute.line, attribute.column));
+            }
+            "points" => {
+                let points = attribute.vertices(usize::MAX, "the position of each LED")?;
+                options.shape = Some((
+                    SegmentKind::Points(points.into_vec()),
+                    attribute.line,
+                    attribute.column,
+                ));
+            }
+            _ => {
+                return Err(attribute.error(
+                    ConfigErrorKind::UnknownKey,
+                    "`density`, `leds`, `arc`, `bezier`, `points`, `gap`, `name` or `tags`",
+                ))
+            }
+        }
+
+        // a segment of points has exactly one LED per point.
+        let is_points = matches!(options.shape, Some((SegmentKind::Points(_), ..)));
+        if is_points && (options.density.is_some() || options.leds.is_some()) {
+            return Err(attribute.error(
+                ConfigErrorKind::DuplicateKey,
+                "either `points` or a `density` or `leds`, not both",
+            ));
+        }
+    }
+    Ok(options)
+}
+
+/// Parses the `[name: ...]` list that may follow a vertex.
+fn vertex_name(
+    attributes: &[Attribute],
+    labels: &mut BTreeSet<String>,
+) -> Result<Option<String>, ConfigError> {
+    let mut name = None;
+    for attribute in attributes {
+        match attribute.key {
+            "name" if name.is_none() => name = Some(attribute.label(labels)?),
+            "name" => return Err(attribute.error(ConfigErrorKind::DuplicateKey, "a single `name`")),
+            _ => return Err(attribute.error(ConfigErrorKind::UnknownKey, "`name`")),
+        }
+    }
+
+    Ok(name)
+}
+
+fn strip_comment(line: &str) -> &str {
+    match line.find('#') {
+        Some(comment_start) => &line[..comment_start],
+        None => line,
+    }
+}
+
+fn parse_vertex(value: &str, line: usize, column: usize) -> Result<Vec2, ConfigError> {
+    parse_components(value, line, column, false).map(Vec3::truncate)
+}
+
+/// Like [parse_vertex], but also accepts `(x, y, z)`.
+fn parse_vertex_3d(value: &str, line: usize, column: usize) -> Result<Vec3, ConfigError> {
+    parse_components(value, line, column, true)
+}
+
+fn parse_components(
+    value: &str,
+    line: usize,
+    column: usize,
+    allow_z: bool,
+) -> Result<Vec3, ConfigError> {
+    let mut lexer = Lexer::new(value, line, column);
+    if lexer.peek() != Some('(') {
+        return Err(lexer.error(
+            ConfigErrorKind::MalformedVec2,
+            value,
+            if allow_z {
+                "a vertex of the form `(x, y)` or `(x, y, z)`"
+            } else {
+                "a vertex of the form `(x, y)`"
+            },
+        ));
+    }
+
+    let vertex = lexer.components(allow_z)?;
+    lexer.expect_end()?;
+    Ok(vertex)
+}
+
+fn parse_density(value: &str, line: usize, column: usize) -> Result<f32, ConfigError> {
+    value
+        .parse()
+        .ok()
+        .filter(|d: &f32| is_valid_density(*d))
+        .ok_or_else(|| ConfigError {
+            kind: ConfigErrorKind::InvalidNumber,
+            line,
+            column,
+            found: value.to_string(),
+            expected: "a positive number",
+        })
+}
+
+/// Returns how many meters one unit of the given name spans.
+fn parse_units(value: &str, line: usize, column: usize) -> Result<f32, ConfigError> {
+    match value {
+        "mm" => Ok(0.001),
+        "cm" => Ok(0.01),
+        "m" => Ok(1.0),
+        "in" => Ok(0.0254),
+        "ft" => Ok(0.3048),
+        _ => Err(ConfigError {
+            kind: ConfigErrorKind::UnexpectedToken,
+            line,
+            column,
+            found: value.to_string(),
+            expected: "`mm`, `cm`, `m`, `in` or `ft`",
+        }),
+    }
+}
+
+fn parse_rotation(value: &str, line: usize, column: usize) -> Result<f32, ConfigError> {
+    value
+        .parse()
+        .ok()
+        .filter(|r: &f32| r.is_finite())
+        .ok_or_else(|| ConfigError {
+            kind: ConfigErrorKind::InvalidNumber,
+            line,
+            column,
+            found: value.to_string(),
+            expected: "an angle in degrees",
+        })
+}
+
+fn parse_segments(
+    lexer: &mut Lexer,
+    density: f32,
+    transform: Affine2,
+) -> Result<(Vec<LineSegment>, Vec<ChainConfig>), ConfigError> {
+    let mut segments = vec![];
+    let mut chains = vec![];
+    let mut chain_start = 0;
+    // chains stay on the same output until a new one is named.
+    let mut output = DEFAULT_OUTPUT.to_string();
+    let mut last_vertex: Option<Vec3> = None;
+    // heights aren't rotated or translated, but are scaled along with the rest of the layout.
+    let z_scale = transform.matrix2.determinant().abs().sqrt();
+    // true at the start of a chain, or right after an arrow.
+    let mut expecting_vertex = true;
+    let mut chain = ChainOptions::default();
+    let mut segment: Option<SegmentOptions> = None;
+    // the label of the current chain's first vertex, and whether the last vertex has had its attributes yet.
+    let mut start_name: Option<String> = None;
+    let mut vertex_named = false;
+    // names must be unique across chains, segments and vertices alike.
+    let mut labels = BTreeSet::new();
+
+    loop {
+        lexer.skip_whitespace();
+        let (line, column) = (lexer.line, lexer.column);
+        let found = lexer.word_at_cursor();
+        match lexer.next_token()? {
+            Some(Token::Vertex(vertex)) if expecting_vertex => {
+                let vertex = transform
+                    .transform_point2(vertex.truncate())
+                    .extend(vertex.z * z_scale);
+                if let Some(start) = last_vertex {
+                    let options = segment.take().unwrap_or_default();
+                    let led_count = match (&options.shape, options.leds) {
+                        (Some((SegmentKind::Points(points), ..)), _) => {
+                            LedCount::Exact(points.len())
+                        }
+                        (_, Some(leds)) => LedCount::Exact(leds),
+                        (_, None) => {
+                            LedCount::Density(options.density.or(chain.density).unwrap_or(density))
+                        }
+                    };
+
+                    let (kind, shape_line, shape_column) =
+                        options.shape.unwrap_or((SegmentKind::Line, line, column));
+                    let kind = kind.transformed(transform);
+                    let mut segment =
+                        LineSegment::new(start.truncate(), vertex.truncate(), kind, led_count)
+                            .ok_or_else(|| ConfigError {
+                                kind: ConfigErrorKind::InvalidGeometry,
+                                line: shape_line,
+                                column: shape_column,
+                                found: "arc".to_string(),
+                                expected: "an arc through three distinct, non-collinear points",
+                            })?;
+                    segment.start_z = start.z;
+                    segment.end_z = vertex.z;
+
+                    if let Some(gaps) = options.gaps {
+                        if let Some(run) = gaps.first_invalid_run(segment.num_leds()) {
+                            return Err(options.gap_errors[run].clone());
+                        }
+                        segment.gaps = gaps;
+                    }
+                    segment.name = options.name;
+                    // chain-wide tags apply to every segment, unless the segment sets its own value.
+                    segment.tags = chain.tags.clone();
+                    segment.tags.extend(options.tags.unwrap_or_default());
+                    segments.push(segment);
+                }
+                last_vertex = Some(vertex);
+                expecting_vertex = false;
+                vertex_named = false;
+            }
+            // attributes right after a vertex label it
+            Some(Token::Attributes(attributes)) if !expecting_vertex && !vertex_named => {
+                let name = vertex_name(&attributes, &mut labels)?;
+                match segments[chain_start..].last_mut() {
+                    Some(segment) => segment.end_name = name,
+                    None => start_name = name,
+                }
+                vertex_named = true;
+            }
+            // attributes at the very start of a chain apply to the whole chain
+            Some(Token::Attributes(attributes)) if expecting_vertex && last_vertex.is_none() => {
+                chain = chain_options(&attributes, &mut labels)?;
+                if let Some(name) = chain.output.take() {
+                    output = name;
+                }
+            }
+            // attributes right after an arrow apply to the segment it draws
+            Some(Token::Attributes(attributes)) if expecting_vertex && segment.is_none() => {
+                segment = Some(segment_options(&attributes, &mut labels)?);
+            }
+            Some(Token::Arrow) if !expecting_vertex => expecting_vertex = true,
+            Some(Token::Break) if !expecting_vertex => {
+                push_chain(
+                    &mut chains,
+                    &mut chain,
+                    start_name.take(),
+                    &output,
+                    chain_start..segments.len(),
+                );
+                chain_start = segments.len();
+                last_vertex = None;
+                expecting_vertex = true;
+                chain = ChainOptions::default();
+            }
+            Some(_) => {
+                return Err(ConfigError {
+                    kind: ConfigErrorKind::UnexpectedToken,
+                    line,
+                    column,
+                    found: found.to_string(),
+                    expected: if expecting_vertex {
+                        "a vertex of the form `(x, y)`"
+                    } else {
+                        "`-->`, `|` or end of input"
+                    },
+                });
+            }
+            None if expecting_vertex => {
+                return Err(lexer.error(
+                    ConfigErrorKind::UnexpectedEnd,
+                    "",
+                    "a vertex of the form `(x, y)`",
+                ));
+            }
+            None => {
+                push_chain(
+                    &mut chains,
+                    &mut chain,
+                    start_name.take(),
+                    &output,
+                    chain_start..segments.len(),
+                );
+                break;
+            }
+        }
+    }
+
+    if segments.is_empty() {
+        return Err(lexer.error(
+            ConfigErrorKind::NoSegments,
+            "",
+            "at least two connected vertices",
+        ));
+    }
+
+    Ok((segments, chains))
+}
+
+fn push_chain(
+    chains: &mut Vec<ChainConfig>,
+    options: &mut ChainOptions,
+    start_name: Option<String>,
+    output: &str,
+    segments: Range<usize>,
+) {
+    // a lone vertex doesn't draw anything, so there's nothing to wire.
+    if segments.is_empty() {
+        return;
+    }
+
+    chains.push(ChainConfig {
+        segments,
+        reversed: options.reversed,
+        offset: options.offset.unwrap_or(0),
+        output: output.to_string(),
+        name: options.name.take(),
+        start_name,
+        group: options.group.take(),
+    });
+}
+
+/// Parses the value of an `include:` line, like `kitchen.yap [translation: (5, 0), rotation: 90, name: kitchen]`.
+fn parse_include(value: &str, line: usize, column: usize) -> Result<Include, ConfigError> {
+    let mut lexer = Lexer::new(value, line, column);
+    let path = lexer.word();
+    if path.is_empty() {
+        return Err(lexer.error(
+            ConfigErrorKind::UnexpectedToken,
+            lexer.word_at_cursor(),
+            "the path of a layout file",
+        ));
+    }
+
+    let mut include = Include {
+        path: path.to_string(),
+        name: None,
+        transform: Affine2::IDENTITY,
+        line,
+        column,
+    };
+
+    lexer.skip_whitespace();
+    if lexer.peek() != Some('[') {
+        lexer.expect_end()?;
+        return Ok(include);
+    }
+
+    let (mut scale, mut rotation, mut translation) = (None, None, None);
+    for attribute in lexer.attributes()? {
+        match attribute.key {
+            "name" if include.name.is_none() => include.name = Some(attribute.name()?.to_string()),
+            "scale" if scale.is_none() => scale = Some(attribute.density()?),
+            "rotation" if rotation.is_none() => {
+                let value = attribute.single_value("an angle in degrees")?;
+                rotation = Some(parse_rotation(value.text, value.line, value.column)?);
+            }
+            "translation" if translation.is_none() => {
+                translation = Some(attribute.vertices(1, "an offset of the form `(x, y)`")?[0]);
+            }
+            "name" | "scale" | "rotation" | "translation" => {
+                return Err(attribute.error(
+                    ConfigErrorKind::DuplicateKey,
+                    "each attribute at most once per include",
+                ))
+            }
+            _ => {
+                return Err(attribute.error(
+                    ConfigErrorKind::UnknownKey,
+                    "`name`, `scale`, `rotation` or `translation`",
+                ))
+            }
+        }
+    }
+    lexer.expect_end()?;
+
+    // like the header's own transform: scaled, rotated about the origin and then translated.
+    include.transform = Affine2::from_scale_angle_translation(
+        Vec2::splat(scale.unwrap_or(1.0)),
+        rotation.unwrap_or(0.0).to_radians(),
+        translation.unwrap_or(Vec2::ZERO),
+    );
+    Ok(include)
+}
+
+/// Parses a config, leaving any `include:` lines for the caller to resolve.
+///
+/// A config with includes doesn't need a `--segments--` section of its own.
+pub(crate) fn parse(s: &str) -> Result<(Config, Vec<Include>), ConfigError> {
+    let mut includes = vec![];
+    let mut center: Option<Vec3> = None;
+    let mut density: Option<f32> = None;
+    let mut units: Option<f32> = None;
+    let mut scale: Option<f32> = None;
+    let mut rotation: Option<f32> = None;
+    let mut translation: Option<Vec2> = None;
+
+    // byte offset of the current line within `s`
+    let mut offset = 0;
+    let mut line_number = 0;
+    let mut segments_start = None;
+
+    for line in s.split_inclusive('\n') {
+        line_number += 1;
+        let line_offset = offset;
+        offset += line.len();
+
+        let trimmed = strip_comment(line).trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        let key_column = line[..indent].chars().count() + 1;
+
+        if trimmed.starts_with(SEGMENT_MARKER) {
+            let section_offset = line_offset + indent + SEGMENT_MARKER.len();
+            segments_start = Some((section_offset, line_number, key_column));
+            break;
+        }
+
+        let Some((key, value)) = trimmed.split_once(':') else {
+            return Err(ConfigError {
+                kind: ConfigErrorKind::UnknownKey,
+                line: line_number,
+                column: key_column,
+                found: trimmed.to_string(),
+                expected: "`center:`, `density:`, a transform, `include:` or `--segments--`",
+            });
+        };
+
+        let value_start = key.len() + 1 + (value.len() - value.trim_start().len());
+        let value_column = key_column + trimmed[..value_start].chars().count();
+        let value = value.trim();
+
+        let duplicate = |expected: &'static str| ConfigError {
+            kind: ConfigErrorKind::DuplicateKey,
+            line: line_number,
+            column: key_column,
+            found: key.to_string(),
+            expected,
+        };
+
+        match key.trim() {
+            "center" => {
+                if center.is_some() {
+                    return Err(duplicate("a single `center:` declaration"));
+                }
+                center = Some(parse_vertex_3d(value, line_number, value_column)?);
+            }
+            "density" => {
+                if density.is_some() {
+                    return Err(duplicate("a single `density:` declaration"));
+                }
+                density = Some(parse_density(value, line_number, value_column)?);
+            }
+            "units" => {
+                if units.is_some() {
+                    return Err(duplicate("a single `units:` declaration"));
+                }
+                units = Some(parse_units(value, line_number, value_column)?);
+            }
+            "scale" => {
+                if scale.is_some() {
+                    return Err(duplicate("a single `scale:` declaration"));
+                }
+                scale = Some(parse_density(value, line_number, value_column)?);
+            }
+            "rotation" => {
+                if rotation.is_some() {
+                    return Err(duplicate("a single `rotation:` declaration"));
+                }
+                rotation = Some(parse_rotation(value, line_number, value_column)?);
+            }
+            "translation" => {
+                if translation.is_some() {
+                    return Err(duplicate("a single `translation:` declaration"));
+                }
+                translation = Some(parse_vertex(value, line_number, value_column)?);
+            }
+            "include" => includes.push(parse_include(value, line_number, value_column)?),
+            _ => {
+                return Err(ConfigError {
+                    kind: ConfigErrorKind::UnknownKey,
+                    line: line_number,
+                    column: key_column,
+                    found: key.to_string(),
+                    expected: "`center:`, `density:`, a transform, `include:` or `--segments--`",
+                });
+            }
+        }
+    }
+
+    if segments_start.is_none() && includes.is_empty() {
+        return Err(ConfigError {
+            kind: ConfigErrorKind::MissingSegmentMarker,
+            line: line_number.max(1),
+            column: 1,
+            found: "".to_string(),
+            expected: "`--segments--`",
+        });
+    }
+
+    let missing = |kind, expected: &'static str| match segments_start {
+        Some((_, marker_line, marker_column)) => ConfigError {
+            kind,
+            line: marker_line,
+            column: marker_column,
+            found: SEGMENT_MARKER.to_string(),
+            expected,
+        },
+        None => ConfigError {
+            kind,
+            line: line_number.max(1),
+            column: 1,
+            found: "".to_string(),
+            expected,
+        },
+    };
+
+    let Some(center_point) = center else {
+        return Err(missing(ConfigErrorKind::MissingCenter, "`center: (x, y)`"));
+    };
+    let Some(density) = density else {
+        return Err(missing(ConfigErrorKind::MissingDensity, "`density: n`"));
+    };
+
+    // coordinates are converted to meters, scaled, rotated about the origin and then translated.
+    let transform = Affine2::from_scale_angle_translation(
+        Vec2::splat(units.unwrap_or(1.0) * scale.unwrap_or(1.0)),
+        rotation.unwrap_or(0.0).to_radians(),
+        translation.unwrap_or(Vec2::ZERO),
+    );
+    let center_z = center_point.z * transform.matrix2.determinant().abs().sqrt();
+    let center_point = transform.transform_point2(center_point.truncate());
+
+    let (line_segments, chains) = match segments_start {
+        Some((section_offset, marker_line, marker_column)) => {
+            let section_column = marker_column + SEGMENT_MARKER.chars().count();
+            let mut lexer = Lexer::new(&s[section_offset..], marker_line, section_column);
+            parse_segments(&mut lexer, density, transform)?
+        }
+        None => (vec![], vec![]),
+    };
+
+    let config = Config {
+        density,
+        center_point,
+        center_z,
+        line_segments,
+        chains,
+    };
+    Ok((config, includes))
+}
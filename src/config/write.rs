@@ -0,0 +1,161 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write as _;
+
+use crate::{
+    config::{
+        ChainConfig, Gaps, LedCount, LineSegment, SegmentKind, BARE_TAG_VALUE, DEFAULT_OUTPUT,
+    },
+    Vec2, Vec3,
+};
+
+/// Writes a layout out in the canonical `.yap` format, such that parsing the result reproduces the same segments.
+///
+/// Each chain is written out with its wiring attributes, one vertex per line.
+/// Floats are written with their shortest round-tripping representation, so no precision is lost.
+pub(crate) fn write(
+    center_point: Vec3,
+    density: f32,
+    line_segments: &[LineSegment],
+    chains: &[ChainConfig],
+) -> String {
+    let mut out = String::new();
+    // writing to a String can't fail
+    let _ = writeln!(out, "center: {}", vertex_3d(center_point));
+    let _ = writeln!(out, "density: {}", density);
+    out.push_str("--segments--\n");
+
+    let mut output = DEFAULT_OUTPUT;
+    for (i, chain) in chains.iter().enumerate() {
+        if i > 0 {
+            out.push_str(" |\n");
+        }
+
+        // outputs carry over from one chain to the next, so only changes need writing.
+        let mut wiring = chain_attributes(chain);
+        if chain.output != output {
+            wiring.push(format!("output: {}", chain.output));
+            output = &chain.output;
+        }
+        if !wiring.is_empty() {
+            let _ = write!(out, "[{}] ", wiring.join(", "));
+        }
+
+        let segments = &line_segments[chain.segments.clone()];
+        out.push_str(&vertex_3d(segments[0].start.extend(segments[0].start_z)));
+        write_vertex_name(&mut out, &chain.start_name);
+        for segment in segments {
+            out.push_str(" -->");
+            let attributes = segment_attributes(segment, density);
+            if !attributes.is_empty() {
+                let _ = write!(out, " [{}]", attributes.join(", "));
+            }
+            let _ = write!(
+                out,
+                "\n    {}",
+                vertex_3d(segment.end.extend(segment.end_z))
+            );
+            write_vertex_name(&mut out, &segment.end_name);
+        }
+    }
+    out.push('\n');
+
+    out
+}
+
+fn chain_attributes(chain: &ChainConfig) -> Vec<String> {
+    let mut attributes = Vec::new();
+    if let Some(name) = &chain.name {
+        attributes.push(format!("name: {}", name));
+    }
+    if let Some(group) = &chain.group {
+        attributes.push(format!("group: {}", group));
+    }
+    if chain.reversed {
+        attributes.push("reversed".into());
+    }
+    if chain.offset > 0 {
+        attributes.push(format!("offset: {}", chain.offset));
+    }
+    attributes
+}
+
+fn write_vertex_name(out: &mut String, name: &Option<String>) {
+    if let Some(name) = name {
+        let _ = write!(out, " [name: {}]", name);
+    }
+}
+
+fn segment_attributes(segment: &LineSegment, density: f32) -> Vec<String> {
+    let mut attributes = Vec::new();
+    if let Some(name) = &segment.name {
+        attributes.push(format!("name: {}", name));
+    }
+    if !segment.tags.is_empty() {
+        let tags: Vec<String> = segment
+            .tags
+            .iter()
+            .map(|(key, value)| match value.as_str() {
+                BARE_TAG_VALUE => key.clone(),
+                _ => format!("{}={}", key, value),
+            })
+            .collect();
+        attributes.push(format!("tags: {}", tags.join(" ")));
+    }
+
+    match &segment.kind {
+        SegmentKind::Line => {}
+        SegmentKind::Arc(through) => attributes.push(format!("arc: {}", vertex(*through))),
+        SegmentKind::QuadraticBezier(control) => {
+            attributes.push(format!("bezier: {}", vertex(*control)))
+        }
+        SegmentKind::CubicBezier(control_1, control_2) => attributes.push(format!(
+            "bezier: {} {}",
+            vertex(*control_1),
+            vertex(*control_2)
+        )),
+        SegmentKind::Points(points) => {
+            let points: Vec<String> = points.iter().map(|point| vertex(*point)).collect();
+            attributes.push(format!("points: {}", points.join(" ")));
+        }
+    }
+
+    match &segment.gaps {
+        Gaps::None => {}
+        Gaps::All => attributes.push("gap".into()),
+        Gaps::Runs(runs) => {
+            let runs: Vec<String> = runs
+                .iter()
+                .map(|run| match run.len() {
+                    1 => format!("{}", run.start),
+                    _ => format!("{}..{}", run.start, run.end),
+                })
+                .collect();
+            attributes.push(format!("gap: {}", runs.join(" ")));
+        }
+    }
+
+    match segment.led_count {
+        // the number of points already determines the LED count.
+        _ if matches!(segment.kind, SegmentKind::Points(_)) => {}
+        LedCount::Density(d) if d.to_bits() == density.to_bits() => {}
+        LedCount::Density(d) => attributes.push(format!("density: {}", d)),
+        LedCount::Exact(leds) => attributes.push(format!("leds: {}", leds)),
+    }
+
+    attributes
+}
+
+fn vertex(v: Vec2) -> String {
+    format!("({}, {})", v.x, v.y)
+}
+
+/// Writes `(x, y, z)`, or just `(x, y)` for vertices on the `z = 0` plane.
+fn vertex_3d(v: Vec3) -> String {
+    if v.z == 0.0 {
+        vertex(v.truncate())
+    } else {
+        format!("({}, {}, {})", v.x, v.y, v.z)
+    }
+}
@@ -11,6 +11,11 @@ pub type Driver<COLOR> = CustomDriver<std::time::Instant, COLOR>;
 mod data;
 pub use data::Data;
 
+#[cfg(feature = "std")]
+mod watcher;
+#[cfg(feature = "std")]
+pub use watcher::ConfigWatcher;
+
 #[derive(Clone, Debug)]
 pub struct Time {
     pub elapsed: Duration,
@@ -18,6 +23,9 @@ pub struct Time {
 }
 
 type SledResult = Result<(), SledError>;
+type StartupCommands<COLOR> = Box<dyn Fn(&mut Sled<COLOR>, &mut Data) -> SledResult>;
+type ComputeCommands<COLOR> = Box<dyn Fn(&Sled<COLOR>, &mut Data, &Time) -> SledResult>;
+type DrawCommands<COLOR> = Box<dyn Fn(&mut Sled<COLOR>, &Data, &Time) -> SledResult>;
 
 /// Drivers are useful for encapsulating everything you need to drive a complicated lighting effect all in one place.
 pub struct CustomDriver<INSTANT, COLOR>
@@ -26,9 +34,9 @@ where
     COLOR: ColorType,
 {
     sled: Option<Sled<COLOR>>,
-    startup_commands: Box<dyn Fn(&mut Sled<COLOR>, &mut Data) -> SledResult>,
-    compute_commands: Box<dyn Fn(&Sled<COLOR>, &mut Data, &Time) -> SledResult>,
-    draw_commands: Box<dyn Fn(&mut Sled<COLOR>, &Data, &Time) -> SledResult>,
+    startup_commands: StartupCommands<COLOR>,
+    compute_commands: ComputeCommands<COLOR>,
+    draw_commands: DrawCommands<COLOR>,
     startup: INSTANT,
     last_update: INSTANT,
 
@@ -158,6 +166,64 @@ where
         self.sled = Some(sled);
     }
 
+    /// Swaps the mounted Sled for a new one, such as an edited version of the same layout, without restarting the effect.
+    ///
+    /// The new Sled takes on the colors of the old one wherever their LEDs share a position (see [Sled::copy_colors_by_position()]),
+    /// then the Driver's [startup commands](CustomDriver::set_startup_commands) are rerun on it. Unlike [CustomDriver::mount()],
+    /// elapsed time keeps counting. If no Sled was mounted, this mounts the new one.
+    ///
+    /// If the startup commands fail, their error is returned and the old Sled stays mounted.
+    pub fn remount(&mut self, mut sled: Sled<COLOR>) -> SledResult {
+        let Some(old) = &self.sled else {
+            self.mount(sled);
+            return Ok(());
+        };
+
+        sled.copy_colors_by_position(old);
+        (self.startup_commands)(&mut sled, &mut self.data)?;
+        self.sled = Some(sled);
+        Ok(())
+    }
+
+    /// Reloads the layout and [remounts](CustomDriver::remount) it if the watched config file has changed. Call this before each [step](CustomDriver::step).
+    ///
+    /// Returns `Ok(true)` if a new layout was mounted. If the edited file fails to load, the error is returned and the old Sled stays mounted,
+    /// so a typo doesn't stop the show; the file will be reloaded again once it next changes.
+    /// ```rust
+    ///# use spatial_led::{Sled, driver::{Driver, ConfigWatcher}};
+    ///# use palette::rgb::Rgb;
+    ///# use std::time::Duration;
+    ///# let path = std::env::temp_dir().join(format!("spatial_led_hot_reload_{}.yap", std::process::id()));
+    ///# let path = path.to_str().unwrap();
+    ///# std::fs::write(path, "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)").unwrap();
+    /// let mut driver = Driver::<Rgb>::new();
+    /// driver.set_startup_commands(|sled, _| {
+    ///     sled.set_all(Rgb::new(1.0, 0.0, 0.0));
+    ///     Ok(())
+    /// });
+    /// driver.mount(Sled::new(path).unwrap());
+    ///
+    /// let mut watcher = ConfigWatcher::new(path).poll_interval(Duration::ZERO);
+    /// assert!(!driver.reload_if_changed(&mut watcher).unwrap());
+    ///
+    /// // meanwhile, in a text editor...
+    /// std::fs::write(path, "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (2.5, 0)").unwrap();
+    ///
+    /// assert!(driver.reload_if_changed(&mut watcher).unwrap());
+    /// assert_eq!(driver.sled().unwrap().num_leds(), 25);
+    ///# std::fs::remove_file(path).unwrap();
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn reload_if_changed(&mut self, watcher: &mut ConfigWatcher) -> Result<bool, SledError> {
+        if !watcher.changed() {
+            return Ok(false);
+        }
+
+        let sled = Sled::new(watcher.path())?;
+        self.remount(sled)?;
+        Ok(true)
+    }
+
     /// Runs the Driver's [compute commands](CustomDriver::set_compute_commands) first, and then runs its [draw commands](CustomDriver::set_draw_commands).
     pub fn step(&mut self) {
         if let Some(sled) = &mut self.sled {
@@ -0,0 +1,66 @@
+use core::time::Duration;
+use std::string::String;
+use std::time::{Instant, SystemTime};
+
+/// Polls a [config file](crate::Sled::new) for changes, so an edited layout can be swapped into a running
+/// [Driver](crate::driver::Driver) without restarting. See [CustomDriver::reload_if_changed()](crate::driver::CustomDriver::reload_if_changed).
+///
+/// Only checks the file's modification time and size, and no more often than its [poll interval](ConfigWatcher::poll_interval).
+pub struct ConfigWatcher {
+    path: String,
+    poll_interval: Duration,
+    last_poll: Option<Instant>,
+    last_seen: Option<(SystemTime, u64)>,
+}
+
+impl ConfigWatcher {
+    /// Starts watching the config file at `path`. Its current contents are considered unchanged.
+    pub fn new(path: impl Into<String>) -> Self {
+        let path = path.into();
+        let last_seen = Self::stamp(&path);
+        ConfigWatcher {
+            path,
+            poll_interval: Duration::from_millis(250),
+            last_poll: None,
+            last_seen,
+        }
+    }
+
+    /// Sets how long to wait between checks of the file. Defaults to 250 milliseconds.
+    pub fn poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Returns the path being watched.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Returns true if the file has changed since the last time this returned true (or since the watcher was created).
+    ///
+    /// Returns false without touching the file if called again within the poll interval.
+    /// A missing file counts as unchanged, as editors often briefly remove a file while saving it.
+    pub fn changed(&mut self) -> bool {
+        if self
+            .last_poll
+            .is_some_and(|last_poll| last_poll.elapsed() < self.poll_interval)
+        {
+            return false;
+        }
+        self.last_poll = Some(Instant::now());
+
+        match Self::stamp(&self.path) {
+            Some(stamp) if Some(stamp) != self.last_seen => {
+                self.last_seen = Some(stamp);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn stamp(path: &str) -> Option<(SystemTime, u64)> {
+        let metadata = std::fs::metadata(path).ok()?;
+        Some((metadata.modified().ok()?, metadata.len()))
+    }
+}
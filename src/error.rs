@@ -1,5 +1,6 @@
 use core::{error::Error, fmt};
 
+use alloc::format;
 use alloc::string::String;
 use alloc::string::ToString as _;
 
@@ -7,19 +8,58 @@ use alloc::string::ToString as _;
 /// Simple error type used by fallible Sled operations.
 pub struct SledError {
     pub message: String,
+    kind: SledErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+/// Describes what caused a [SledError], for callers that want to do more than print the message.
+pub enum SledErrorKind {
+    /// Any failure that does not carry structured information.
+    #[default]
+    Other,
+    /// A layout config could not be parsed. See [ConfigError].
+    Config(ConfigError),
 }
 
 impl SledError {
     pub fn new(message: String) -> Self {
-        SledError { message }
+        SledError {
+            message,
+            kind: SledErrorKind::Other,
+        }
     }
 
     pub fn from_error(e: impl Error) -> Self {
+        SledError::new(e.to_string())
+    }
+
+    /// Wraps a [ConfigError], using its [Display](fmt::Display) output as the error message.
+    pub fn from_config_error(e: ConfigError) -> Self {
         SledError {
             message: e.to_string(),
+            kind: SledErrorKind::Config(e),
+        }
+    }
+
+    /// Returns what caused the error.
+    pub fn kind(&self) -> &SledErrorKind {
+        &self.kind
+    }
+
+    /// Returns `Some(&ConfigError)` if this error was caused by a malformed layout config.
+    pub fn config_error(&self) -> Option<&ConfigError> {
+        match &self.kind {
+            SledErrorKind::Config(e) => Some(e),
+            SledErrorKind::Other => None,
         }
     }
 
+    /// Notes that the error happened while reading the named file, keeping its [kind](SledErrorKind).
+    pub(crate) fn in_file(mut self, path: &str) -> Self {
+        self.message = format!("In `{}`: {}", path, self.message);
+        self
+    }
+
     pub fn as_err<T>(self) -> Result<T, Self> {
         Err(self)
     }
@@ -31,6 +71,12 @@ impl core::convert::From<&str> for SledError {
     }
 }
 
+impl core::convert::From<ConfigError> for SledError {
+    fn from(value: ConfigError) -> Self {
+        SledError::from_config_error(value)
+    }
+}
+
 impl fmt::Display for SledError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "{}", self.message)
@@ -38,3 +84,106 @@ impl fmt::Display for SledError {
 }
 
 impl Error for SledError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// The different ways a layout config can fail to parse.
+pub enum ConfigErrorKind {
+    /// No `--segments--` marker was found.
+    MissingSegmentMarker,
+    /// No `center: (x, y)` line was found before the segments.
+    MissingCenter,
+    /// No `density: n` line was found before the segments.
+    MissingDensity,
+    /// A header key was declared more than once.
+    DuplicateKey,
+    /// The same `name` was given to more than one chain, segment or vertex.
+    DuplicateName,
+    /// A header line did not start with a recognized key.
+    UnknownKey,
+    /// A vertex was not of the form `(x, y)`.
+    MalformedVec2,
+    /// A number could not be parsed, or was out of the allowed range.
+    InvalidNumber,
+    /// Something other than what the grammar allows was found.
+    UnexpectedToken,
+    /// The config ended while more input was expected.
+    UnexpectedEnd,
+    /// The segments section does not describe any line segments.
+    NoSegments,
+    /// The described geometry cannot be constructed, i.e. an arc through three collinear points.
+    InvalidGeometry,
+    /// An included file could not be read, or could not be given a group name.
+    InvalidInclude,
+    /// A file includes itself, directly or through other includes.
+    CyclicInclude,
+}
+
+impl ConfigErrorKind {
+    fn description(&self) -> &'static str {
+        match self {
+            ConfigErrorKind::MissingSegmentMarker => {
+                "no segment marker of form `--segments--` found"
+            }
+            ConfigErrorKind::MissingCenter => "no center point descriptor found",
+            ConfigErrorKind::MissingDensity => "no density descriptor found",
+            ConfigErrorKind::DuplicateKey => "duplicate key",
+            ConfigErrorKind::DuplicateName => "duplicate name",
+            ConfigErrorKind::UnknownKey => "unknown key",
+            ConfigErrorKind::MalformedVec2 => "malformed Vec2",
+            ConfigErrorKind::InvalidNumber => "invalid number",
+            ConfigErrorKind::UnexpectedToken => "unexpected token",
+            ConfigErrorKind::UnexpectedEnd => "unexpected end of input",
+            ConfigErrorKind::NoSegments => "no line segments found",
+            ConfigErrorKind::InvalidGeometry => "invalid geometry",
+            ConfigErrorKind::InvalidInclude => "invalid include",
+            ConfigErrorKind::CyclicInclude => "file includes itself",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+/// Describes where and why a layout config failed to parse.
+///
+/// Lines and columns are 1-based, columns are counted in characters.
+/// ```rust
+/// # use spatial_led::{Sled, ConfigErrorKind};
+/// # use palette::rgb::Rgb;
+/// let config = "center: (0, 0)\ndensity: 30\n--segments--\n(0, 0) --> (1, O)";
+/// let err = Sled::<Rgb>::new_from_str(config).unwrap_err();
+/// let config_err = err.config_error().unwrap();
+///
+/// assert_eq!(config_err.kind, ConfigErrorKind::InvalidNumber);
+/// assert_eq!((config_err.line, config_err.column), (4, 16));
+/// assert_eq!(config_err.found, "O");
+/// ```
+pub struct ConfigError {
+    pub kind: ConfigErrorKind,
+    pub line: usize,
+    pub column: usize,
+    /// The offending text. Empty if the error was caused by the end of input.
+    pub found: String,
+    /// A short hint describing what the parser was expecting instead.
+    pub expected: &'static str,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let found = if self.found.is_empty() {
+            "end of input".to_string()
+        } else {
+            format!("`{}`", self.found)
+        };
+
+        write!(
+            f,
+            "Error parsing config file at line {}, column {}: {}. Expected {}, found {}.",
+            self.line,
+            self.column,
+            self.kind.description(),
+            self.expected,
+            found
+        )
+    }
+}
+
+impl Error for ConfigError {}
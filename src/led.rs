@@ -1,4 +1,6 @@
 use crate::Vec2;
+#[cfg(feature = "3d")]
+use crate::Vec3;
 
 #[cfg(not(feature = "std"))]
 use num_traits::float::Float as _;
@@ -6,15 +8,16 @@ use num_traits::float::Float as _;
 use crate::color::ColorType;
 
 #[derive(Copy, Clone)]
-
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// An LED in our Sled configuration, representing both the color of the LED as well as it's spatial information.
 pub struct Led<Color: ColorType> {
     pub color: Color,
     position: Vec2,
     angle: f32,
     distance: f32,
-    index: u16,
-    segment: u8,
+    index: u32,
+    segment: u32,
+    gap: bool,
 }
 
 /// *All properties listed below are pre-calculated on construction;
@@ -25,27 +28,34 @@ impl<Color: ColorType> Led<Color> {
     pub(crate) fn new(
         color: Color,
         position: Vec2,
-        index: u16,
-        segment: u8,
+        index: u32,
+        segment: u32,
         center_point: Vec2,
+        gap: bool,
     ) -> Self {
-        let offset = position - center_point;
-        let angle = offset.y.atan2(offset.x);
-        let distance = offset.length();
+        let mut led = Led {
+            color,
+            position,
+            angle: 0.0,
+            distance: 0.0,
+            index,
+            segment,
+            gap,
+        };
+        led.set_center_point(center_point);
+        led
+    }
+
+    /// Recalculates `angle` and `distance` relative to a new `center_point`.
+    pub(crate) fn set_center_point(&mut self, center_point: Vec2) {
+        let offset = self.position - center_point;
+        self.angle = offset.y.atan2(offset.x);
+        self.distance = offset.length();
         // let mut angle = direction.angle_between(Vec2::new(1.0, 0.0));
         // if angle < 0.0 {
         //     angle += TAU;
         // }
         // let distance = position.distance(center_point);
-
-        Led {
-            color,
-            position,
-            angle,
-            distance,
-            index,
-            segment,
-        }
     }
 
     /// Returns the position of the Led in world space.
@@ -70,14 +80,20 @@ impl<Color: ColorType> Led<Color> {
     }
 
     /// Returns the index of the Led, keeping in mind that Leds in a Sled are treated in memory as one continuous strip.
-    pub fn index(&self) -> u16 {
+    pub fn index(&self) -> u32 {
         self.index
     }
 
     /// Returns the index of the LineSegment this Led belongs to.
-    pub fn segment(&self) -> u8 {
+    pub fn segment(&self) -> u32 {
         self.segment
     }
+
+    /// Returns true if this Led sits in a gap; a slot in the strip that is wired but never lit,
+    /// such as a cut-off or hidden LED. Gap Leds keep their index but are ignored by spatial queries.
+    pub fn is_gap(&self) -> bool {
+        self.gap
+    }
 }
 
 impl<Color: ColorType> PartialEq for Led<Color> {
@@ -117,6 +133,7 @@ impl<Color: ColorType> core::fmt::Debug for Led<Color> {
             .field("distance", &self.distance)
             .field("index", &self.index)
             .field("segment", &self.segment)
+            .field("gap", &self.gap)
             .finish()
     }
 }
@@ -126,3 +143,141 @@ impl<Color: ColorType> core::fmt::Display for Led<Color> {
         write!(f, "{}: {:?}", self.index, self.color)
     }
 }
+
+#[cfg(feature = "3d")]
+#[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An LED in a [Sled3](crate::Sled3), representing both the color of the LED as well as its position in 3D space.
+pub struct Led3<Color: ColorType> {
+    pub color: Color,
+    position: Vec3,
+    direction: Vec3,
+    distance: f32,
+    index: u32,
+    segment: u32,
+    gap: bool,
+}
+
+/// *All properties listed below are pre-calculated on construction;
+/// there is no substantial overhead for calling these methods.*
+#[cfg(feature = "3d")]
+impl<Color: ColorType> Led3<Color> {
+    /// Constructs an LED struct.
+    /// Fields like `direction` and `distance` are derived from `center_point`.
+    pub(crate) fn new(
+        color: Color,
+        position: Vec3,
+        index: u32,
+        segment: u32,
+        center_point: Vec3,
+        gap: bool,
+    ) -> Self {
+        let mut led = Led3 {
+            color,
+            position,
+            direction: Vec3::ZERO,
+            distance: 0.0,
+            index,
+            segment,
+            gap,
+        };
+        led.set_center_point(center_point);
+        led
+    }
+
+    /// Recalculates `direction` and `distance` relative to a new `center_point`.
+    pub(crate) fn set_center_point(&mut self, center_point: Vec3) {
+        let offset = self.position - center_point;
+        self.direction = offset.normalize_or_zero();
+        self.distance = offset.length();
+    }
+
+    /// Returns the position of the Led in world space.
+    pub fn position(&self) -> Vec3 {
+        self.position
+    }
+
+    /// Returns the direction from the Sled3's `center_point` to this Led. A normalized vector, or zero if the Led sits on the `center_point`.
+    pub fn direction(&self) -> Vec3 {
+        self.direction
+    }
+
+    /// Returns the distance from the Sled3's `center_point` to this Led.
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+
+    /// Returns the index of the Led, keeping in mind that Leds in a Sled3 are treated in memory as one continuous strip.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Returns the index of the LineSegment this Led belongs to.
+    pub fn segment(&self) -> u32 {
+        self.segment
+    }
+
+    /// Returns true if this Led sits in a gap. See [Led::is_gap()].
+    pub fn is_gap(&self) -> bool {
+        self.gap
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> PartialEq for Led3<Color> {
+    fn eq(&self, other: &Self) -> bool {
+        self.index() == other.index()
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> Eq for Led3<Color> {}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> PartialOrd for Led3<Color> {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> Ord for Led3<Color> {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.index.cmp(&other.index())
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> core::hash::Hash for Led3<Color> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.index.hash(state);
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> core::fmt::Debug for Led3<Color> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Led3")
+            .field("color", &self.color)
+            .field(
+                "position",
+                &(self.position.x, self.position.y, self.position.z),
+            )
+            .field(
+                "direction",
+                &(self.direction.x, self.direction.y, self.direction.z),
+            )
+            .field("distance", &self.distance)
+            .field("index", &self.index)
+            .field("segment", &self.segment)
+            .field("gap", &self.gap)
+            .finish()
+    }
+}
+
+#[cfg(feature = "3d")]
+impl<Color: ColorType> core::fmt::Display for Led3<Color> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {:?}", self.index, self.color)
+    }
+}
@@ -21,7 +21,7 @@
 //! <summary><strong>What Sled does <ins>not</ins> do:</strong></summary>
 //!
 //! - It does not interface directly with your GPIO pins to control your LED hardware. Each project will be different, so it's up to you to bring your own glue. Check out the [Raspberry Pi example](https://github.com/DavJCosby/spatial_led_examples/tree/main/raspberry_pi) to get an idea what that might look like.
-//! - It does not allow you to represent your LEDs in 3D space. Could be a fun idea in the future, but it's just not planned for the time being.
+//! - It does not model full 3D scenes. Layouts can be given heights and loaded into a `Sled3` with the `3d` feature, but curves are still drawn in the `x, y` plane, and angle-based methods like `map_by_angle()` and `within_sector()` are only available in 2D. `Sled3` also has no spatial index yet, so `closest_to()`, `furthest_from()` and `within_dist_from()` scan every LED instead of the nearby buckets `Sled` uses.
 //!</details>
 //!
 //! See the [spatial_led_examples](https://github.com/DavJCosby/spatial_led_examples) repository for examples of Sled in action!
@@ -509,6 +509,8 @@
 //! Opt-in:
 //! - `libm` : Needed for some `no_std` environments.
 //! - `core-simd` (Nightly) : Enables portable SIMD support for use by glam.
+//! - `serde` : Implements `Serialize` and `Deserialize` for [Sled], [Led], [Filter], and [SledBuilder] layouts. Works without `std`.
+//! - `3d` : Adds `Sled3` and `Led3` for layouts with `(x, y, z)` vertices, positioned with [Vec3].
 //! </details>
 
 extern crate alloc;
@@ -531,13 +533,23 @@ pub mod driver;
 /// Scheduler is an optional feature that can be disabled by turning off the `scheduler` feature flag.
 pub mod scheduler;
 
-pub use error::SledError;
+pub use config::{Chain, Lint, LintKind, LintSeverity, Segment, SledBuilder};
+pub use error::{ConfigError, ConfigErrorKind, SledError, SledErrorKind};
 /// Equivalent to `Result<(), SledError>`
 pub type SledResult = Result<(), SledError>;
+/// Using [glam](https://crates.io/crates/glam)'s implementation. See [Sled::transform()].
+pub use glam::Affine2;
 /// Using [glam](https://crates.io/crates/glam)'s implementation.
 pub use glam::Vec2;
+/// Using [glam](https://crates.io/crates/glam)'s implementation.
+pub use glam::Vec3;
 pub use led::Led;
+#[cfg(feature = "3d")]
+pub use led::Led3;
 pub use spatial_led::Filter;
+pub use spatial_led::Rect;
 pub use spatial_led::Sled;
+#[cfg(feature = "3d")]
+pub use spatial_led::Sled3;
 
 pub mod time;
@@ -13,9 +13,11 @@ impl<Color: ColorType> Sled<Color> {
 
         let mut intersections = smallvec::smallvec![];
         for (seg_index, segment) in self.line_segments.iter().enumerate() {
-            if let Some(t) = segment.intersects_line(start, end) {
+            for t in segment.intersects_line(start, end) {
                 let index = self.alpha_to_index(t, seg_index);
-                intersections.push(index);
+                if !self.leds[index].is_gap() {
+                    intersections.push(index);
+                }
             }
         }
 
@@ -42,8 +44,8 @@ impl<Color: ColorType> Sled<Color> {
         let intersecting_indices = self.raycast_for_indices(pos, dir);
         intersecting_indices
             .iter()
-            .map(|i| *i as u16)
-            .collect::<BTreeSet<u16>>()
+            .map(|i| *i as u32)
+            .collect::<BTreeSet<u32>>()
             .into()
     }
 
@@ -3,6 +3,7 @@ use alloc::collections::{btree_set, BTreeSet};
 use crate::{color::ColorType, led::Led, spatial_led::Sled};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 /// A Filter is a set of LEDs that can be obtained via one of [Sled's](Sled) getter-type methods.
 ///
 /// They are particularly useful for scenarios when you have computationally expensive calculations and you want to limit which LEDs those calculations are run on. Once you've created a filter, you can save it to [Data](crate::driver::Data) for use in draw/compute stages. Using this pattern, we can pre-compute important sets at startup and then store them to the driver for later usage.
@@ -28,7 +29,7 @@ use crate::{color::ColorType, led::Led, spatial_led::Sled};
 /// });
 /// ```
 pub struct Filter {
-    led_indices: BTreeSet<u16>,
+    led_indices: BTreeSet<u32>,
 }
 
 impl<Color: ColorType> From<&[Led<Color>]> for Filter {
@@ -41,8 +42,8 @@ impl<Color: ColorType> From<&[Led<Color>]> for Filter {
     }
 }
 
-impl From<BTreeSet<u16>> for Filter {
-    fn from(value: BTreeSet<u16>) -> Self {
+impl From<BTreeSet<u32>> for Filter {
+    fn from(value: BTreeSet<u32>) -> Self {
         Filter { led_indices: value }
     }
 }
@@ -88,8 +89,8 @@ impl Filter {
 }
 
 impl IntoIterator for Filter {
-    type Item = u16;
-    type IntoIter = btree_set::IntoIter<u16>;
+    type Item = u32;
+    type IntoIter = btree_set::IntoIter<u32>;
 
     fn into_iter(self) -> Self::IntoIter {
         self.led_indices.into_iter()
@@ -97,8 +98,8 @@ impl IntoIterator for Filter {
 }
 
 impl IntoIterator for &Filter {
-    type Item = u16;
-    type IntoIter = btree_set::IntoIter<u16>;
+    type Item = u32;
+    type IntoIter = btree_set::IntoIter<u32>;
 
     fn into_iter(self) -> Self::IntoIter {
         // this doesn't seem right; revisit
@@ -106,9 +107,9 @@ impl IntoIterator for &Filter {
     }
 }
 
-impl FromIterator<u16> for Filter {
-    fn from_iter<T: IntoIterator<Item = u16>>(iter: T) -> Self {
-        let mut set = BTreeSet::<u16>::new();
+impl FromIterator<u32> for Filter {
+    fn from_iter<T: IntoIterator<Item = u32>>(iter: T) -> Self {
+        let mut set = BTreeSet::<u32>::new();
         for i in iter {
             set.insert(i);
         }
@@ -117,8 +118,8 @@ impl FromIterator<u16> for Filter {
     }
 }
 
-impl Extend<u16> for Filter {
-    fn extend<T: IntoIterator<Item = u16>>(&mut self, iter: T) {
+impl Extend<u32> for Filter {
+    fn extend<T: IntoIterator<Item = u32>>(&mut self, iter: T) {
         for i in iter {
             self.led_indices.insert(i);
         }
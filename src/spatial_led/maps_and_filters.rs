@@ -202,11 +202,47 @@ impl<Color: ColorType> Sled<Color> {
             led.color = dist_to_color_map(dist);
         });
     }
+
+    /// Maps LEDs to a color depending on their signed distance from the infinite line passing through `a` and `b`.
+    /// Distances are positive to the left of the line, looking from `a` towards `b`, and negative to the right.
+    /// Handy for wipes: move the line across the layout and light up whatever it has passed.
+    ///
+    /// If `a` and `b` are the same point, each LED is mapped by its (unsigned) distance from it.
+    ///
+    /// ```rust
+    /// # use spatial_led::{Sled, Filter, Vec2};
+    /// # use palette::rgb::Rgb;
+    /// # let mut sled = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+    /// // a soft-edged wipe moving rightwards, currently at x = 1
+    /// let edge = 1.0;
+    /// sled.map_by_dist_to_line(Vec2::new(edge, 0.0), Vec2::new(edge, 1.0), |dist| {
+    ///     let brightness = (0.5 + dist * 4.0).clamp(0.0, 1.0);
+    ///     Rgb::new(brightness, brightness, brightness)
+    /// });
+    /// ```
+    pub fn map_by_dist_to_line(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        dist_to_color_map: impl Fn(f32) -> Color,
+    ) {
+        let dir = (b - a).normalize_or_zero();
+        self.leds.iter_mut().for_each(|led| {
+            let offset = led.position() - a;
+            let dist = if dir == Vec2::ZERO {
+                offset.length()
+            } else {
+                dir.perp_dot(offset)
+            };
+            led.color = dist_to_color_map(dist);
+        });
+    }
 }
 
 /// Filters
 impl<Color: ColorType> Sled<Color> {
     /// Returns a [Filter] containing all LEDs that meet a certain criteria.
+    /// LEDs in a [gap](Led::is_gap) are never included.
     ///
     /// ```rust
     /// # use spatial_led::{Sled, Filter, Vec2};
@@ -216,10 +252,16 @@ impl<Color: ColorType> Sled<Color> {
     /// sled.set_filter(&odd, Rgb::new(1.0, 1.0, 1.0));
     /// ```
     pub fn filter(&self, filter: impl Fn(&Led<Color>) -> bool) -> Filter {
-        let filtered: BTreeSet<u16> = self
+        let filtered: BTreeSet<u32> = self
             .leds
             .iter()
-            .filter_map(|led| if filter(led) { Some(led.index()) } else { None })
+            .filter_map(|led| {
+                if !led.is_gap() && filter(led) {
+                    Some(led.index())
+                } else {
+                    None
+                }
+            })
             .collect();
         filtered.into()
     }
@@ -294,4 +336,25 @@ impl<Color: ColorType> Sled<Color> {
             dist_filter(dist)
         })
     }
+
+    /// Returns a [Filter] containing all LEDs on one side of the line through `point`: the side `normal` points towards.
+    /// LEDs exactly on the line are included. `normal` doesn't need to be normalized.
+    ///
+    /// ```rust
+    /// # use spatial_led::{Sled, Filter, Vec2};
+    /// # use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// let right_side = sled.filter_half_plane(Vec2::new(2.05, 0.0), Vec2::new(1.0, 0.0));
+    /// assert_eq!(right_side.len(), 79);
+    /// sled.set_filter(&right_side, Rgb::new(1.0, 1.0, 1.0));
+    /// ```
+    pub fn filter_half_plane(&self, point: Vec2, normal: Vec2) -> Filter {
+        self.filter(|led| (led.position() - point).dot(normal) >= 0.0)
+    }
 }
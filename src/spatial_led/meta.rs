@@ -1,5 +1,8 @@
 use core::ops::Range;
 
+use alloc::collections::BTreeSet;
+use alloc::format;
+use alloc::string::{String, ToString};
 use alloc::vec;
 use alloc::vec::Vec;
 
@@ -8,11 +11,11 @@ use num_traits::float::Float as _;
 
 use crate::{
     color::ColorType,
-    config::{Config, LineSegment},
+    config::{parse_points, ChainConfig, Config, LineSegment, POINTS_DENSITY},
     error::SledError,
     led::Led,
-    spatial_led::Sled,
-    Vec2,
+    spatial_led::{Filter, Sled, SpatialIndex},
+    Affine2, Chain, SledBuilder, Vec2,
 };
 
 /// # Construction, output, and basic sled info
@@ -31,6 +34,45 @@ impl<COLOR: ColorType> Sled<COLOR> {
     /// ```
     /// * `center` is a 2D reference point you can use to speed up draw calls. At initialization, directions, distances, etc relative to this point are pre-calculated for each Led.
     ///  * `density` represents how many LEDs per unit we can expect for the line segments below.
+    ///  * `units`, `scale`, `rotation` and `translation` are optional, and move the layout from the coordinates it was drawn in to the ones your effects expect. Every vertex, control point and the center are converted from `units` (`mm`, `cm`, `m`, `in` or `ft`) to meters, multiplied by `scale`, rotated `rotation` degrees counter-clockwise about the origin, and finally offset by `translation: (x, y)`. `density` is measured after the transform, so it's in LEDs per meter whenever `units` is given. See also [Sled::transform()].
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (2000, 1500)   # straight from CAD, in millimeters
+    ///         units: mm
+    ///         translation: (-2, -1.5) # put the origin in the middle of the room
+    ///         density: 30             # LEDs per meter
+    ///         --segments--
+    ///         (0, 0) --> (4000, 0) --> (4000, 3000)
+    ///     ").unwrap();
+    ///
+    ///     assert!(sled.center_point().abs_diff_eq(Vec2::ZERO, 1e-5));
+    ///     assert_eq!(sled.num_leds(), 210);
+    ///     assert!(sled.get(209).unwrap().position().abs_diff_eq(Vec2::new(2.0, 1.5), 1e-5));
+    ///     ```
+    ///  * `include: path/to/room.yap` merges another layout file's chains into this one, after this file's own. Paths are relative to the including file, or to the current directory for [Sled::new_from_str()]. An optional list places the included layout, after its own transform keys: it's multiplied by `scale`, rotated `rotation` degrees counter-clockwise and offset by `translation: (x, y)`, in meters. The including file's own transform keys and `density` don't apply to it. A config with includes doesn't need a `--segments--` section of its own.
+    ///
+    ///     Each include's chains are put in a group, named after the file unless given a `name`, so they can be found with [Sled::group()]. Labels inside an included file are prefixed with its group, like `bar/north_wall`.
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 30
+    ///         include: ./benches/config.yap [name: lobby]
+    ///         include: ./benches/config.yap [name: bar, rotation: 90, translation: (10, 0)]
+    ///     ").unwrap();
+    ///
+    ///     let (lobby, bar) = (sled.group("lobby").unwrap(), sled.group("bar").unwrap());
+    ///     assert_eq!(sled.num_segments(), 10);
+    ///     assert_eq!(lobby.len() + bar.len(), sled.num_leds());
+    ///     assert!(sled.groups().eq(["lobby", "bar"]));
+    ///
+    ///     let in_lobby = sled.get(0).unwrap().position();
+    ///     let in_bar = sled.get(lobby.len()).unwrap().position();
+    ///     assert!(in_bar.abs_diff_eq(Vec2::new(10.0 - in_lobby.y, in_lobby.x), 1e-5));
+    ///     ```
     ///  * `(x, y) --> (x, y)` Indicates a line segment spanning between those two connected vertices. If you wish to introduce a break between vertices, you must replace one of the `-->` separators with a `|`. Like this:
     ///     ```yaml, no_run
     ///    --segments--
@@ -44,6 +86,145 @@ impl<COLOR: ColorType> Sled<COLOR> {
     ///     --> (3.5, 0) | (2, 2)
     ///     --> (-2, 2) --> (-2, 0)
     ///     ```
+    ///  * `#` starts a comment that runs until the end of the line. Comments may appear anywhere in the file, including between (or inside) the vertices of a chain:
+    ///     ```rust
+    ///     # use spatial_led::Sled;
+    ///     # use palette::rgb::Rgb;
+    ///     let commented = Sled::<Rgb>::new_from_str("
+    ///         ## living room, measured from the doorway
+    ///         center: (0.0, 0.5) # eye level
+    ///         density: 30.0
+    ///         --segments-- # clockwise from the left wall
+    ///         (-2, 0) --> (0.5, -1) # under the window
+    ///         --> (3.5, 0) | # break for the door
+    ///         (2, # door frame
+    ///          2) --> (-2, 2)
+    ///     ").unwrap();
+    ///     assert_eq!(commented.num_segments(), 3);
+    ///     ```
+    ///  * `[...]` attribute lists change how many LEDs a strip has. Placed at the start of a chain, they apply to every segment in that chain. Placed right after a `-->`, they apply to just that segment.
+    ///     - `density: n` overrides the global density.
+    ///     - `leds: n` pins a segment to exactly `n` LEDs, regardless of its length. Only allowed after a `-->`.
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 30
+    ///         --segments--
+    ///         [density: 60] (0, 0) --> (1, 0) --> [leds: 47] (1, 1) |
+    ///         (2, 0) --> (3, 0)
+    ///     ").unwrap();
+    ///
+    ///     assert_eq!(sled.segment(0).unwrap().len(), 60);
+    ///     assert_eq!(sled.segment(1).unwrap().len(), 47);
+    ///     assert_eq!(sled.segment(2).unwrap().len(), 30);
+    ///     ```
+    ///  * Segments can also be curved by adding one of the following after a `-->`. LEDs on curves are spaced evenly by arc length.
+    ///     - `arc: (x, y)` draws a circular arc that passes through `(x, y)`. If the segment starts and ends at the same point, it draws a full circle with `(x, y)` on the opposite side.
+    ///     - `bezier: (x, y)` draws a quadratic Bézier curve with one control point.
+    ///     - `bezier: (x1, y1) (x2, y2)` draws a cubic Bézier curve with two control points.
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 30
+    ///         --segments--
+    ///         (1, 0) --> [arc: (-1, 0)] (1, 0) | # ring lamp
+    ///         (-2, -2) --> [bezier: (0, -1), leds: 40] (2, -2) # curved shelf
+    ///     ").unwrap();
+    ///
+    ///     assert!(sled.segment(0).unwrap().into_iter().all(|i| {
+    ///         let distance = sled.get(i as usize).unwrap().distance();
+    ///         (distance - 1.0).abs() < 0.01
+    ///     }));
+    ///     ```
+    ///  * By default, LED indices follow each chain in the direction it's drawn. If the data line is wired differently, chains accept two more attributes. Positions are unaffected; only [indices](Led::index) and the order of [colors()](Sled::colors) change.
+    ///     - `reversed` means the data line enters at the chain's last vertex and runs backwards.
+    ///     - `offset: n` means the data line enters `n` LEDs along the chain (after reversing), wrapping around to the chain's start once it reaches the end. Handy for closed loops.
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 10
+    ///         --segments--
+    ///         [reversed] (0, 0) --> (1, 0) | # fed from the right
+    ///         [offset: 5] (0, 1) --> (1, 1)  # fed from the middle
+    ///     ").unwrap();
+    ///
+    ///     let position = |index| sled.get(index).unwrap().position();
+    ///     assert!(position(0).abs_diff_eq(Vec2::new(1.0, 0.0), 1e-5));
+    ///     assert!(position(9).abs_diff_eq(Vec2::new(0.1, 0.0), 1e-5));
+    ///     assert!(position(10).abs_diff_eq(Vec2::new(0.6, 1.0), 1e-5));
+    ///     assert!(position(15).abs_diff_eq(Vec2::new(0.1, 1.0), 1e-5));
+    ///     ```
+    ///  * `output: name` assigns a chain to a named output (i.e. a separate data pin), along with every chain after it that doesn't name its own. Each output's LEDs are given a contiguous range of indices, in the order outputs are first named. See [Sled::outputs()] and [Sled::colors_for_output()].
+    ///  * `points: (x1, y1) (x2, y2) ...` places one LED at each listed point, in order, instead of spacing LEDs evenly along the segment. Useful for fixtures whose LED positions were measured rather than drawn. The number of points sets the segment's LED count, so `density` and `leds` can't be used alongside it. See also [Sled::new_from_points()].
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 10
+    ///         --segments--
+    ///         (0, 0) --> (1, 0) --> [points: (1.1, 0.3) (0.9, 0.5) (1.2, 0.8)] (1, 1)
+    ///     ").unwrap();
+    ///
+    ///     assert_eq!(sled.segment(1).unwrap().len(), 3);
+    ///     assert_eq!(sled.get(11).unwrap().position(), Vec2::new(0.9, 0.5));
+    ///     ```
+    ///  * `gap` marks LEDs that still take up a slot on the data line but should never light up, like dead pixels or LEDs hidden behind a corner. Placed after a `-->`, a bare `gap` covers the whole segment, while `gap: 0..3 17` covers only the listed LEDs, counted from the start of the segment as it's drawn. Ranges exclude their end. Gap LEDs keep their [index](Led::index), but are left out of spatial queries like [Sled::filter()], [Sled::closest_to()] and [Sled::within_dist()], and always output `COLOR::default()`. See [Led::is_gap()].
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let mut sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 10
+    ///         --segments--
+    ///         (0, 0) --> [gap: 0..2 9] (1, 0) # first two and last LEDs are dead
+    ///         --> [gap] (1, 1) # hidden behind the shelf
+    ///         --> (0, 1)
+    ///     ").unwrap();
+    ///
+    ///     assert_eq!(sled.num_leds(), 30);
+    ///     assert_eq!(sled.gaps().len(), 13);
+    ///     assert!(sled.within_dist_from(0.25, Vec2::new(0.0, 0.0)).is_empty());
+    ///     assert_eq!(sled.closest_to(Vec2::new(1.0, 0.4)).index(), 8);
+    ///
+    ///     sled.set_all(Rgb::new(1.0, 0.0, 0.0));
+    ///     assert_eq!(sled.colors().filter(|color| color.red == 0.0).count(), 13);
+    ///     ```
+    ///  * `name: label` gives a chain (at its start), a segment (after its `-->`) or a vertex (in a list right after it) a label, so it can be found with [Sled::segment_by_name()], [Sled::chain_by_name()], [Sled::vertex_by_name()] or [Sled::named()] no matter how the layout is renumbered. Each label may only be used once.
+    ///     ```rust
+    ///     # use spatial_led::Sled;
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 10
+    ///         --segments--
+    ///         [name: shelf] (0, 0) --> [name: left] (1, 0) [name: middle] --> (2, 0)
+    ///     ").unwrap();
+    ///
+    ///     assert!(sled.segment_by_name("left").unwrap().into_iter().eq(0..10));
+    ///     assert_eq!(sled.vertex_by_name("middle").unwrap().index(), 9);
+    ///     ```
+    ///  * `tags: zone=bar facing=down diffused` attaches arbitrary facts to a segment's LEDs, or to every segment in a chain if placed at its start. A tag without a value, like `diffused`, has the value `true`. See [Sled::tags_of()] and [Sled::filter_by_tag()].
+    ///  * Vertices and the `center` may be written as `(x, y, z)` to describe a 3D layout, which `Sled3` (behind the `3d` feature) works with. A Sled sees the layout from above and ignores heights, except that segments climbing between different heights hold more LEDs, and heights are kept when the layout is [written back out](Sled::to_config_string).
+    ///     ```rust
+    ///     # use spatial_led::{Sled, Vec2};
+    ///     # use palette::rgb::Rgb;
+    ///     let sled = Sled::<Rgb>::new_from_str("
+    ///         center: (0, 0)
+    ///         density: 10
+    ///         --segments--
+    ///         (0, 0, 0) --> (3, 0, 4) # a ramp, 5m long
+    ///     ").unwrap();
+    ///
+    ///     assert_eq!(sled.num_leds(), 50);
+    ///     assert!(sled.get(49).unwrap().position().abs_diff_eq(Vec2::new(3.0, 0.0), 1e-5));
+    ///     ```
     #[cfg(feature = "std")]
     pub fn new(config_file_path: &str) -> Result<Self, SledError> {
         let config = Config::from_toml_file(config_file_path)?;
@@ -51,51 +232,288 @@ impl<COLOR: ColorType> Sled<COLOR> {
     }
 
     /// Works like [Sled::new()] but rather than reading the contents of a config file from disk, allows you to pass in the same information as a `&str`.
+    ///
+    /// To describe a layout in code without going through text, see [SledBuilder](crate::SledBuilder).
+    ///
+    /// If the config is malformed, the returned [SledError] carries a [ConfigError](crate::ConfigError)
+    /// describing the line, column and text that could not be parsed. See [SledError::config_error()].
     pub fn new_from_str(string: &str) -> Result<Self, SledError> {
         let config = Config::from_str(string)?;
         Sled::new_from_config(config)
     }
 
-    fn new_from_config(config: Config) -> Result<Self, SledError> {
+    /// Constructs a Sled with one LED at each of the given positions, in wiring order, rather than from line segments.
+    /// Useful for irregular fixtures like LED nets or hand-placed pixels, where each LED's position has been measured.
+    ///
+    /// All spatial methods work as usual. Methods that follow the layout's path, like [Sled::at_dir()] and [Sled::at_dist()],
+    /// treat the LEDs as if they were connected by straight lines, in order. The layout has a single [segment](Sled::segment).
+    ///
+    /// To group LEDs into segments, see [Sled::new_from_csv()] or [Chain::from_points()](crate::Chain::from_points).
+    ///
+    /// O(LEDS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let positions = [
+    ///     Vec2::new(0.1, 0.2),
+    ///     Vec2::new(0.9, 0.3),
+    ///     Vec2::new(0.4, 1.1),
+    ///     Vec2::new(-0.6, 0.8),
+    /// ];
+    /// let sled = Sled::<Rgb>::new_from_points(Vec2::new(0.0, 0.5), &positions).unwrap();
+    ///
+    /// assert_eq!(sled.num_leds(), 4);
+    /// assert_eq!(sled.closest_to(Vec2::new(0.5, 1.0)).index(), 2);
+    /// assert_eq!(sled.within_dist(0.7).len(), 2);
+    /// assert!(sled.positions().eq(positions));
+    /// ```
+    pub fn new_from_points(center_point: Vec2, points: &[Vec2]) -> Result<Self, SledError> {
+        SledBuilder::new(center_point, POINTS_DENSITY)
+            .chain(Chain::from_points(points.iter().copied()))
+            .build()
+    }
+
+    /// Works like [Sled::new_from_points()], but reads the positions from CSV text, such as the output of a camera mapping tool.
+    ///
+    /// Each row is either `x, y` or `x, y, label`, in wiring order. Consecutive rows that share a label are grouped into one
+    /// [segment](Sled::segment); rows without a label share the empty label. Blank lines are ignored, as is a header row if the first row doesn't start with a number.
+    ///
+    /// If a row is malformed, the returned [SledError] carries a [ConfigError](crate::ConfigError) describing where.
+    ///
+    /// O(LEDS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let csv = "\
+    /// x,y,strand
+    /// 0.0, 1.0, a
+    /// 0.5, 1.2, a
+    /// 1.1, 0.9, b
+    /// 1.3, 0.2, b
+    /// 1.0, -0.4, b
+    /// ";
+    /// let sled = Sled::<Rgb>::new_from_csv(Vec2::ZERO, csv).unwrap();
+    ///
+    /// assert_eq!(sled.num_leds(), 5);
+    /// assert_eq!(sled.num_segments(), 2);
+    /// assert_eq!(sled.segment(1).unwrap().len(), 3);
+    /// assert_eq!(sled.closest_to(Vec2::new(1.2, 0.1)).segment(), 1);
+    /// ```
+    pub fn new_from_csv(center_point: Vec2, csv: &str) -> Result<Self, SledError> {
+        parse_points(csv)?
+            .into_iter()
+            .fold(
+                SledBuilder::new(center_point, POINTS_DENSITY),
+                |builder, points| builder.chain(Chain::from_points(points)),
+            )
+            .build()
+    }
+
+    pub(crate) fn new_from_config(config: Config) -> Result<Self, SledError> {
+        if let Some(lint) = config.lint_errors().first() {
+            return SledError::new(lint.to_string()).as_err();
+        }
+
         let leds_per_segment = Sled::<COLOR>::leds_per_segment(&config);
+        let (path_to_physical, outputs) = Sled::<COLOR>::wiring(&config.chains, &leds_per_segment);
         let leds = Sled::build_led_list(
             &leds_per_segment,
+            &path_to_physical,
             &config.line_segments,
             &config.center_point,
-        );
+        )?;
         let line_segment_endpoint_indices =
             Sled::<COLOR>::line_segment_endpoint_indices(&leds_per_segment);
-        let vertex_indices = Sled::<COLOR>::vertex_indices(&config);
+        let vertex_indices = Sled::<COLOR>::vertex_indices(&config)
+            .into_iter()
+            .map(|i| path_to_physical[i])
+            .collect();
         let num_leds = leds.len();
-        let index_of_closest = leds
-            .iter()
-            .min_by(|l, r| l.distance().partial_cmp(&r.distance()).unwrap())
-            .unwrap()
-            .index() as usize;
-
-        let index_of_furthest = leds
-            .iter()
-            .max_by(|l, r| l.distance().partial_cmp(&r.distance()).unwrap())
-            .unwrap()
-            .index() as usize;
+        let live_leds: Vec<&Led<COLOR>> = leds.iter().filter(|led| !led.is_gap()).collect();
+        if live_leds.is_empty() {
+            return SledError::new("Every LED in the layout is inside a gap.".to_string()).as_err();
+        }
 
-        let domain = Sled::calc_domain(&leds);
+        let (index_of_closest, index_of_furthest) =
+            Sled::calc_closest_and_furthest(live_leds.iter().copied());
+        let domain = Sled::calc_domain(&live_leds);
+        let furthest_candidates = Sled::furthest_candidates(
+            &config.line_segments,
+            &line_segment_endpoint_indices,
+            &path_to_physical,
+            &leds,
+        );
+        let spatial_index = SpatialIndex::new(
+            live_leds
+                .iter()
+                .map(|led| (led.position(), led.index()))
+                .collect(),
+        );
 
         Ok(Sled {
             center_point: config.center_point,
+            center_z: config.center_z,
             leds,
             num_leds,
             density: config.density,
             line_segments: config.line_segments,
+            chains: config.chains,
+            outputs,
             index_of_closest,
             index_of_furthest,
             domain,
+            spatial_index,
+            furthest_candidates,
+            gap_color: COLOR::default(),
             // utility lookup tables
             line_segment_endpoint_indices,
             vertex_indices,
+            path_to_physical,
         })
     }
 
+    /// Writes the Sled's layout back out in the [config file](Sled::new) format.
+    ///
+    /// The output is canonical rather than a copy of the original file: comments are dropped and
+    /// chain-wide densities are written out per segment. Chains and their wiring are kept as they were.
+    /// Passing the result to [Sled::new_from_str()] is guaranteed to reproduce the same LEDs.
+    ///
+    /// Colors are not part of the layout and are not written.
+    ///
+    /// O(SEGMENTS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0.5)
+    ///     density: 30
+    ///     --segments--
+    ///     (-2, 0) --> (0.5, -1) # bottom
+    ///     --> [density: 60] (3.5, 0) |
+    ///     (1, 0) --> [arc: (-1, 0)] (1, 0)
+    /// ").unwrap();
+    ///
+    /// let config = sled.to_config_string();
+    /// assert_eq!(config, "\
+    /// center: (0, 0.5)
+    /// density: 30
+    /// --segments--
+    /// (-2, 0) -->
+    ///     (0.5, -1) --> [density: 60]
+    ///     (3.5, 0) |
+    /// (1, 0) --> [arc: (-1, 0)]
+    ///     (1, 0)
+    /// ");
+    ///
+    /// let reloaded = Sled::<Rgb>::new_from_str(&config).unwrap();
+    /// assert!(reloaded.positions().eq(sled.positions()));
+    /// ```
+    pub fn to_config_string(&self) -> String {
+        Config::to_string(
+            self.center_point.extend(self.center_z),
+            self.density,
+            &self.line_segments,
+            &self.chains,
+        )
+    }
+
+    /// Returns a [SledBuilder] describing this Sled's layout, so it can be modified and rebuilt.
+    ///
+    /// Each chain in the layout, along with its wiring, becomes one [Chain](crate::Chain).
+    ///
+    /// O(SEGMENTS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Chain, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let sled = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+    /// let extended: Sled<Rgb> = sled
+    ///     .to_builder()
+    ///     .chain(Chain::from_vertices([Vec2::new(5.0, 0.0), Vec2::new(5.0, 1.0)]))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(extended.num_segments(), sled.num_segments() + 1);
+    /// assert!(extended.positions().take(sled.num_leds()).eq(sled.positions()));
+    /// ```
+    pub fn to_builder(&self) -> SledBuilder {
+        SledBuilder::from_layout(
+            self.center_point,
+            self.center_z,
+            self.density,
+            &self.line_segments,
+            &self.chains,
+        )
+    }
+
+    /// Moves, rotates and scales the whole layout, including its [center point](Sled::center_point), by the given transform.
+    /// Positions, angles, distances, the [domain](Sled::domain) and all lookup tables are recomputed.
+    ///
+    /// LED counts, indices, wiring and colors are unchanged; densities are scaled along with the layout to match.
+    /// Use this to move a layout drawn in one coordinate system into another at runtime. To do the same in a config file, see the transform keys in [Sled::new()].
+    ///
+    /// Returns an [error](SledError) if the transform can't be inverted or isn't finite, or if the layout contains an arc
+    /// and the transform doesn't scale uniformly, as the arc would become an ellipse.
+    ///
+    /// O(LEDS + SEGMENTS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Affine2, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (1, 0) --> [arc: (1.5, 0.5)] (1, 1)
+    /// ").unwrap();
+    /// sled.set(3, Rgb::new(1.0, 0.0, 0.0)).unwrap();
+    ///
+    /// let quarter_turn = Affine2::from_angle_translation(core::f32::consts::FRAC_PI_2, Vec2::new(5.0, 0.0));
+    /// sled.transform(quarter_turn).unwrap();
+    ///
+    /// assert!(sled.center_point().abs_diff_eq(Vec2::new(5.0, 0.0), 1e-5));
+    /// assert!(sled.get(9).unwrap().position().abs_diff_eq(Vec2::new(5.0, 1.0), 1e-5));
+    /// assert_eq!(sled.get(3).unwrap().color, Rgb::new(1.0, 0.0, 0.0));
+    ///
+    /// // arcs can't be stretched into ellipses
+    /// assert!(sled.transform(Affine2::from_scale(Vec2::new(2.0, 1.0))).is_err());
+    /// ```
+    pub fn transform(&mut self, transform: Affine2) -> Result<(), SledError> {
+        let determinant = transform.matrix2.determinant();
+        if !transform.is_finite() || determinant == 0.0 {
+            return SledError::new("Transforms must be finite and invertible.".to_string())
+                .as_err();
+        }
+
+        let line_segments = self
+            .line_segments
+            .iter()
+            .enumerate()
+            .map(|(index, segment)| {
+                segment.transformed(transform).ok_or_else(|| {
+                    SledError::new(format!(
+                        "Segment {} is an arc, which can only be rotated, reflected, translated and uniformly scaled.",
+                        index
+                    ))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let config = Config {
+            center_point: transform.transform_point2(self.center_point),
+            center_z: self.center_z * determinant.abs().sqrt(),
+            density: self.density / determinant.abs().sqrt(),
+            line_segments,
+            chains: self.chains.clone(),
+        };
+
+        let mut transformed = Sled::new_from_config(config)?;
+        for (led, original) in transformed.leds.iter_mut().zip(&self.leds) {
+            led.color = original.color;
+        }
+        *self = transformed;
+
+        Ok(())
+    }
+
     /// Returns a read-only iterator over the system's [LEDs](Led).
     ///
     /// If you need owned copies of these values, `.collect()` this iterator into a Vector.
@@ -118,6 +536,8 @@ impl<COLOR: ColorType> Sled<COLOR> {
 
     /// Returns an Iterator over the 32-bit RGB colors for each [LED](Led) in the system
     ///
+    /// LEDs in a [gap](Led::is_gap) always output `COLOR::default()`, whatever they've been set to.
+    ///
     /// O(LEDS)
     ///
     /// ```rust
@@ -132,7 +552,7 @@ impl<COLOR: ColorType> Sled<COLOR> {
     /// }
     /// ```
     pub fn colors(&self) -> impl Iterator<Item = &COLOR> + '_ {
-        self.leds.iter().map(|led| &led.color)
+        self.leds.iter().map(|led| self.output_color(led))
     }
 
     /// Returns an Iterator over Vec2s, representing the position of each [LED](Led) in the system.
@@ -143,10 +563,109 @@ impl<COLOR: ColorType> Sled<COLOR> {
     }
 
     /// Returns an Iterator over tuple pairs of the color and position of each [LED](Led) in the system.
+    /// Like [Sled::colors()], LEDs in a gap always output `COLOR::default()`.
     ///
     /// O(LEDS)
     pub fn colors_and_positions(&self) -> impl Iterator<Item = (COLOR, Vec2)> + '_ {
-        self.leds.iter().map(|led| (led.color, led.position()))
+        self.leds
+            .iter()
+            .map(|led| (*self.output_color(led), led.position()))
+    }
+
+    /// Returns the name of each output declared in the [config file](Sled::new), in the order their LEDs appear in [Sled::colors()].
+    /// Layouts that don't name any outputs have a single output called `default`.
+    ///
+    /// O(OUTPUTS)
+    /// ```rust
+    ///# use spatial_led::Sled;
+    ///# use palette::rgb::Rgb;
+    /// let sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     [output: left_wall] (-1, 0) --> (-1, 1) |
+    ///     [output: right_wall] (1, 0) --> (1, 1) |
+    ///     (1, 1) --> (0, 2) | # continues on right_wall
+    ///     [output: left_wall] (-1, 1) --> (0, 2)
+    /// ").unwrap();
+    ///
+    /// assert!(sled.outputs().eq(["left_wall", "right_wall"]));
+    /// assert_eq!(sled.output_range("left_wall"), Some(0..24));
+    /// assert_eq!(sled.output_range("right_wall"), Some(24..48));
+    /// ```
+    pub fn outputs(&self) -> impl Iterator<Item = &str> {
+        self.outputs.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the range of [indices](Led::index) driven by the output with the given name, if it exists.
+    ///
+    /// An LED's index within its own output is its index minus the start of this range.
+    ///
+    /// O(OUTPUTS)
+    pub fn output_range(&self, name: &str) -> Option<Range<usize>> {
+        self.outputs
+            .iter()
+            .find(|(output, _)| output == name)
+            .map(|(_, range)| range.clone())
+    }
+
+    /// Returns the [LEDs](Led) driven by the output with the given name, in wiring order, if it exists.
+    ///
+    /// O(OUTPUTS + LEDS_IN_OUTPUT)
+    pub fn leds_for_output(&self, name: &str) -> Option<impl Iterator<Item = &Led<COLOR>>> {
+        let range = self.output_range(name)?;
+        Some(self.leds[range].iter())
+    }
+
+    /// Returns the colors of the LEDs driven by the output with the given name, in wiring order, if it exists.
+    /// Useful for sending each output's data to its own pin.
+    ///
+    /// O(OUTPUTS + LEDS_IN_OUTPUT)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     [output: left_wall] (-1, 0) --> (-1, 1) |
+    ///     [output: right_wall] (1, 0) --> (1, 1)
+    /// ").unwrap();
+    ///
+    /// // spatial queries still span every output
+    /// sled.set_within_dist_from(1.0, Vec2::new(1.0, 0.0), Rgb::new(1.0, 0.0, 0.0));
+    ///
+    /// for name in ["left_wall", "right_wall"] {
+    ///     let colors: Vec<Rgb> = sled.colors_for_output(name).unwrap().copied().collect();
+    ///     // write colors to this output's pin...
+    /// }
+    /// ```
+    pub fn colors_for_output(&self, name: &str) -> Option<impl Iterator<Item = &COLOR>> {
+        Some(
+            self.leds_for_output(name)?
+                .map(|led| self.output_color(led)),
+        )
+    }
+
+    /// Returns a [Filter] containing each [LED](Led) driven by the output with the given name, if it exists.
+    ///
+    /// O(OUTPUTS + LEDS_IN_OUTPUT)
+    pub fn output(&self, name: &str) -> Option<Filter> {
+        let range = self.output_range(name)?;
+        Some(self.leds[range].into())
+    }
+
+    /// Returns a [Filter] containing each [LED](Led) that sits in a [gap](Led::is_gap).
+    ///
+    /// O(LEDS)
+    pub fn gaps(&self) -> Filter {
+        let gaps: BTreeSet<u32> = self
+            .leds
+            .iter()
+            .filter(|led| led.is_gap())
+            .map(|led| led.index())
+            .collect();
+        gaps.into()
     }
 
     /// Returns the static reference point declared in the [config file](Sled::new).
@@ -156,9 +675,61 @@ impl<COLOR: ColorType> Sled<COLOR> {
         self.center_point
     }
 
+    /// Moves the reference point that [angles](Led::angle), [distances](Led::distance) and the
+    /// `_from`-less spatial methods (like [Sled::within_dist()] or [Sled::at_angle()]) are measured from.
+    /// Each LED's angle and distance are recalculated, as are [Sled::closest()] and [Sled::furthest()]. Colors are kept.
+    ///
+    /// Useful for effects whose origin follows something around, like a tracked person.
+    ///
+    /// O(LEDS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (1, 0)
+    /// ").unwrap();
+    /// sled.set(4, Rgb::new(1.0, 0.0, 0.0)).unwrap();
+    ///
+    /// sled.set_center_point(Vec2::new(1.0, 0.0));
+    ///
+    /// assert_eq!(sled.closest().index(), 9);
+    /// assert!((sled.get(4).unwrap().distance() - 0.5).abs() < 1e-5);
+    /// assert_eq!(sled.get(4).unwrap().color, Rgb::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn set_center_point(&mut self, center_point: Vec2) {
+        self.center_point = center_point;
+        for led in &mut self.leds {
+            led.set_center_point(center_point);
+        }
+
+        let (index_of_closest, index_of_furthest) =
+            Sled::calc_closest_and_furthest(self.leds.iter().filter(|led| !led.is_gap()));
+        self.index_of_closest = index_of_closest;
+        self.index_of_furthest = index_of_furthest;
+    }
+
     /// Returns the total number of [LEDs](Led) in the system.
     ///
     /// O(1)
+    /// ```rust
+    ///# use spatial_led::{Sled, SledBuilder, Chain, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// // a facade with 100 rows of 1,000 LEDs each
+    /// let facade: Sled<Rgb> = (0..100)
+    ///     .fold(SledBuilder::new(Vec2::ZERO, 100.0), |builder, row| {
+    ///         let y = row as f32 * 0.1;
+    ///         builder.chain(Chain::from_vertices([Vec2::new(0.0, y), Vec2::new(10.0, y)]))
+    ///     })
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(facade.num_leds(), 100_000);
+    /// assert_eq!(facade.get(99_999).unwrap().index(), 99_999);
+    /// assert_eq!(facade.get(99_999).unwrap().segment(), 99);
+    /// ```
     pub fn num_leds(&self) -> usize {
         self.num_leds
     }
@@ -193,35 +764,107 @@ impl<COLOR: ColorType> Sled<COLOR> {
         config
             .line_segments
             .iter()
-            .map(|line| line.num_leds(config.density))
+            .map(|line| line.num_leds())
             .collect()
     }
 
     fn build_led_list(
         leds_per_segment: &[usize],
+        path_to_physical: &[usize],
         line_segments: &[LineSegment],
         center_point: &Vec2,
-    ) -> Vec<Led<COLOR>> {
-        let mut leds = vec![];
+    ) -> Result<Vec<Led<COLOR>>, SledError> {
+        let num_leds: usize = leds_per_segment.iter().sum();
+        if u32::try_from(num_leds).is_err() || u32::try_from(line_segments.len()).is_err() {
+            return SledError::new(format!(
+                "Layout has {} LEDs across {} segments; at most {} of each are supported.",
+                num_leds,
+                line_segments.len(),
+                u32::MAX
+            ))
+            .as_err();
+        }
+
+        let mut leds = Vec::with_capacity(num_leds);
         let default_color = COLOR::default();
 
         for (segment_index, segment_size) in leds_per_segment.iter().enumerate() {
             for i in 0..*segment_size {
                 let segment = &line_segments[segment_index];
-                let alpha = (i + 1) as f32 / *segment_size as f32;
-                let pos = segment.start.lerp(segment.end, alpha);
+                let pos = segment.led_position(i);
+                // both casts are lossless, given the check above.
                 let led = Led::new(
                     default_color,
                     pos,
-                    leds.len() as u16,
-                    segment_index as u8,
+                    path_to_physical[leds.len()] as u32,
+                    segment_index as u32,
                     *center_point,
+                    segment.gaps.contains(i),
                 );
 
                 leds.push(led);
             }
         }
-        leds
+
+        // LEDs are laid out along the drawn path, but stored in the order they're wired.
+        leds.sort_unstable_by_key(|led| led.index());
+        Ok(leds)
+    }
+
+    /// Maps each LED's position along the drawn path to its index along the data lines,
+    /// accounting for each chain's output, wiring direction and start offset.
+    ///
+    /// Outputs are laid out one after the other in the order they're first named,
+    /// so each one covers a contiguous range of indices.
+    fn wiring(
+        chains: &[ChainConfig],
+        leds_per_segment: &[usize],
+    ) -> (Vec<usize>, Vec<(String, Range<usize>)>) {
+        let chain_lens: Vec<usize> = chains
+            .iter()
+            .map(|chain| leds_per_segment[chain.segments.clone()].iter().sum())
+            .collect();
+
+        let mut outputs: Vec<(String, Range<usize>)> = vec![];
+        for (chain, len) in chains.iter().zip(&chain_lens) {
+            match outputs.iter_mut().find(|(name, _)| *name == chain.output) {
+                Some((_, range)) => range.end += len,
+                None => outputs.push((chain.output.clone(), 0..*len)),
+            }
+        }
+
+        let mut next_start = 0;
+        for (_, range) in &mut outputs {
+            let len = range.end;
+            *range = next_start..next_start + len;
+            next_start += len;
+        }
+
+        // where the next chain on each output starts
+        let mut output_cursors: Vec<usize> = outputs.iter().map(|(_, range)| range.start).collect();
+        let mut path_to_physical = Vec::with_capacity(next_start);
+
+        for (chain, chain_len) in chains.iter().zip(chain_lens) {
+            if chain_len == 0 {
+                continue;
+            }
+
+            let output_index = outputs
+                .iter()
+                .position(|(name, _)| *name == chain.output)
+                .unwrap();
+            let chain_start = output_cursors[output_index];
+            output_cursors[output_index] += chain_len;
+
+            let offset = chain.offset % chain_len;
+            for i in 0..chain_len {
+                let along_wiring = if chain.reversed { chain_len - 1 - i } else { i };
+                let physical = (along_wiring + chain_len - offset) % chain_len;
+                path_to_physical.push(chain_start + physical);
+            }
+        }
+
+        (path_to_physical, outputs)
     }
 
     fn line_segment_endpoint_indices(leds_per_segment: &Vec<usize>) -> Vec<(usize, usize)> {
@@ -235,6 +878,33 @@ impl<COLOR: ColorType> Sled<COLOR> {
         line_segment_endpoint_indices
     }
 
+    /// Returns the first and last LED of each unbroken run of lit LEDs along a straight segment.
+    /// Distance from a point along a line peaks at one of its ends, so the furthest LED from anywhere is among these.
+    /// Curves can bulge outwards, so layouts with any get `None` and are searched through the spatial index instead.
+    fn furthest_candidates(
+        line_segments: &[LineSegment],
+        line_segment_endpoint_indices: &[(usize, usize)],
+        path_to_physical: &[usize],
+        leds: &[Led<COLOR>],
+    ) -> Option<Vec<usize>> {
+        if line_segments.iter().any(|segment| segment.is_curved()) {
+            return None;
+        }
+
+        let mut candidates = vec![];
+        for (start, end) in line_segment_endpoint_indices {
+            let segment = &path_to_physical[*start..*end];
+            let lit = |i: usize| segment.get(i).is_some_and(|led| !leds[*led].is_gap());
+            for (i, led) in segment.iter().enumerate() {
+                if lit(i) && (i == 0 || !lit(i - 1) || !lit(i + 1)) {
+                    candidates.push(*led);
+                }
+            }
+        }
+
+        Some(candidates)
+    }
+
     fn vertex_indices(config: &Config) -> Vec<usize> {
         let mut vertex_indices = vec![];
 
@@ -252,7 +922,7 @@ impl<COLOR: ColorType> Sled<COLOR> {
                 vertex_indices.push(last_index);
             }
 
-            let num_leds = line.num_leds(config.density);
+            let num_leds = line.num_leds();
             vertex_indices.push(last_index + num_leds - 1);
 
             last_index += num_leds;
@@ -262,7 +932,29 @@ impl<COLOR: ColorType> Sled<COLOR> {
         vertex_indices
     }
 
-    fn calc_domain(leds: &Vec<Led<COLOR>>) -> Range<Vec2> {
+    /// Returns the indices of the LEDs closest to and furthest from the center point. `leds` must not be empty.
+    fn calc_closest_and_furthest<'a>(leds: impl Iterator<Item = &'a Led<COLOR>>) -> (usize, usize)
+    where
+        COLOR: 'a,
+    {
+        let mut closest: Option<&Led<COLOR>> = None;
+        let mut furthest: Option<&Led<COLOR>> = None;
+        for led in leds {
+            if closest.map_or(true, |closest| led.distance() < closest.distance()) {
+                closest = Some(led);
+            }
+            if furthest.map_or(true, |furthest| led.distance() >= furthest.distance()) {
+                furthest = Some(led);
+            }
+        }
+
+        (
+            closest.unwrap().index() as usize,
+            furthest.unwrap().index() as usize,
+        )
+    }
+
+    fn calc_domain(leds: &[&Led<COLOR>]) -> Range<Vec2> {
         let mut min_x = f32::MAX;
         let mut min_y = f32::MAX;
 
@@ -282,11 +974,34 @@ impl<COLOR: ColorType> Sled<COLOR> {
         Vec2::new(min_x, min_y)..Vec2::new(max_x, max_y)
     }
 
+    /// Returns every LED that isn't in a gap, i.e. those that spatial queries consider.
+    pub(crate) fn live_leds(&self) -> impl Iterator<Item = &Led<COLOR>> {
+        self.leds.iter().filter(|led| !led.is_gap())
+    }
+
+    /// Returns the color an LED actually outputs; always `COLOR::default()` for gaps.
+    fn output_color<'a>(&'a self, led: &'a Led<COLOR>) -> &'a COLOR {
+        if led.is_gap() {
+            &self.gap_color
+        } else {
+            &led.color
+        }
+    }
+
     pub(crate) fn alpha_to_index(&self, segment_alpha: f32, segment_index: usize) -> usize {
-        let segment = &self.line_segments[segment_index];
-        let startpoint_index = self.line_segment_endpoint_indices[segment_index].0;
-        let leds_in_segment = segment.num_leds(self.density) as f32;
+        let (startpoint_index, endpoint_index) = self.line_segment_endpoint_indices[segment_index];
+        let leds_in_segment = (endpoint_index - startpoint_index) as f32;
+
+        let path_index =
+            (startpoint_index + (segment_alpha * leds_in_segment).floor() as usize) % self.num_leds;
+        self.path_to_physical[path_index]
+    }
 
-        (startpoint_index + (segment_alpha * leds_in_segment).floor() as usize) % self.num_leds
+    /// Returns the range of path positions covered by segments `first..=last`.
+    /// Map these through `path_to_physical` to index into `leds`.
+    pub(crate) fn path_range(&self, first: usize, last: usize) -> Range<usize> {
+        let (start, _) = self.line_segment_endpoint_indices[first];
+        let (_, end) = self.line_segment_endpoint_indices[last];
+        start..end
     }
 }
@@ -1,8 +1,14 @@
 use core::ops::Range;
 
+use alloc::string::String;
 use alloc::vec::Vec;
 
-use crate::{color::ColorType, config::LineSegment, led::Led, Vec2};
+use crate::{
+    color::ColorType,
+    config::{ChainConfig, LineSegment},
+    led::Led,
+    Vec2,
+};
 
 #[allow(dead_code)]
 #[derive(Clone, Debug)]
@@ -32,16 +38,30 @@ use crate::{color::ColorType, config::LineSegment, led::Led, Vec2};
 /// ```
 pub struct Sled<COLOR: ColorType> {
     center_point: Vec2,
+    // only used by 3D layouts, but kept so they survive a round trip through a planar Sled.
+    center_z: f32,
     leds: Vec<Led<COLOR>>,
     num_leds: usize,
     density: f32,
     line_segments: Vec<LineSegment>,
+    chains: Vec<ChainConfig>,
+    // each output's name and the range of indices it drives
+    outputs: Vec<(String, Range<usize>)>,
     // utility lookup tables
     line_segment_endpoint_indices: Vec<(usize, usize)>,
     vertex_indices: Vec<usize>,
+    // `leds` is stored in the order LEDs are wired, but segment endpoint
+    // indices count along the drawn path. This maps from the latter to the former.
+    path_to_physical: Vec<usize>,
     index_of_closest: usize,
     index_of_furthest: usize,
     domain: Range<Vec2>,
+    // buckets lit LEDs by position, to speed up nearest-neighbour and radius queries
+    spatial_index: SpatialIndex,
+    // where the furthest LED from any point could be, if the layout is all straight lines
+    furthest_candidates: Option<Vec<usize>>,
+    // what gap LEDs output, regardless of the color they've been set to
+    gap_color: COLOR,
 }
 
 // goofy spacing to preserve order after auto-formatting.
@@ -61,3 +81,17 @@ mod maps_and_filters;
 
 mod filter;
 pub use filter::Filter;
+
+mod rect;
+pub use rect::Rect;
+
+mod spatial_index;
+use spatial_index::SpatialIndex;
+
+#[cfg(feature = "serde")]
+mod serialization;
+
+#[cfg(feature = "3d")]
+mod sled3;
+#[cfg(feature = "3d")]
+pub use sled3::Sled3;
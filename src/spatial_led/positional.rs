@@ -1,9 +1,11 @@
-use alloc::collections::BTreeSet;
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use core::f32::consts::TAU;
 
 use crate::{
     color::ColorType,
     led::Led,
-    spatial_led::{Filter, Sled},
+    spatial_led::{Filter, Rect, Sled},
     Vec2,
 };
 
@@ -17,25 +19,26 @@ impl<Color: ColorType> Sled<Color> {
     /* closest getters/setters */
 
     /// Returns the index of the [LED](Led) closest to a given point.
+    /// LEDs in a [gap](Led::is_gap) are never returned. If several LEDs are equally close, the lowest index wins.
     ///
-    /// O(SEGMENTS)
+    /// Sleds bucket their LEDs into a grid on construction, so only LEDs in cells near `pos` are checked.
+    /// The further `pos` is from the layout, the more cells have to be searched before one with LEDs turns up.
+    ///
+    /// O(NEARBY_LEDS)
     ///
     pub fn index_of_closest_to(&self, pos: Vec2) -> usize {
-        // get the closest point on each segment and bundle relevant info,
-        // then find the closest of those points
-        let (alpha, _dist_sq, segment_index) = self
-            .line_segments
-            .iter()
-            .enumerate()
-            .map(|(index, segment)| {
-                let (closest, alpha) = segment.closest_to_point(pos);
-                let dist_sq = closest.distance_squared(pos);
-                (alpha, dist_sq, index)
-            })
-            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .unwrap();
-
-        self.alpha_to_index(alpha, segment_index)
+        match self.spatial_index.k_closest_to(pos, 1).first() {
+            Some((_, index)) => *index as usize,
+            // the grid only comes up empty if every LED is in a gap.
+            None => {
+                self.leds
+                    .iter()
+                    .map(|led| (led.index(), led.position().distance_squared(pos)))
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .unwrap()
+                    .0 as usize
+            }
+        }
     }
 
     /// Returns the [LED](Led) closest to the center point.
@@ -47,7 +50,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Returns the [LED](Led) closest to a given point.
     ///
-    /// O(SEGMENTS)
+    /// O(NEARBY_LEDS)
     pub fn closest_to(&self, pos: Vec2) -> &Led<Color> {
         let index_of_closest = self.index_of_closest_to(pos);
         &self.leds[index_of_closest]
@@ -72,7 +75,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Modulates the color of the [LED](Led) closest to a given point.
     ///
-    /// O(SEGMENTS)
+    /// O(NEARBY_LEDS)
     ///  
     ///```rust
     ///# use spatial_led::{Sled, SledError, Vec2};
@@ -99,30 +102,93 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Sets the color of the [LED](Led) closest to a given point.
     ///
-    /// O(SEGMENTS)
+    /// O(NEARBY_LEDS)
     pub fn set_closest_to(&mut self, pos: Vec2, color: Color) {
         let index_of_closest = self.index_of_closest_to(pos);
         self.leds[index_of_closest].color = color;
     }
 
+    /// Returns a [Filter] containing the `k` [LEDs](Led) closest to a given point, or every LED if there are fewer than `k`.
+    /// LEDs in a [gap](Led::is_gap) are never included. Ties at the edge of the group go to the lowest indices.
+    ///
+    /// Like [Sled::index_of_closest_to()], only LEDs in grid cells near `pos` are checked.
+    ///
+    /// O(NEARBY_LEDS * k)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// // light up a small glow around a particle near the bottom wall
+    /// let particle = Vec2::new(1.03, 0.2);
+    /// let glow = sled.k_closest_to(particle, 3);
+    /// assert!(glow.into_iter().eq([8, 9, 10]));
+    ///
+    /// sled.set_k_closest_to(particle, 3, Rgb::new(1.0, 1.0, 1.0));
+    /// ```
+    pub fn k_closest_to(&self, pos: Vec2, k: usize) -> Filter {
+        self.spatial_index
+            .k_closest_to(pos, k)
+            .into_iter()
+            .map(|(_, index)| index)
+            .collect()
+    }
+
+    /// Modulates the color of the `k` [LEDs](Led) closest to a given point. See [Sled::k_closest_to()].
+    ///
+    /// Returns false if no LEDs were changed, which only happens when `k` is 0.
+    ///
+    /// O(NEARBY_LEDS * k)
+    pub fn modulate_k_closest_to<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        pos: Vec2,
+        k: usize,
+        color_rule: F,
+    ) -> bool {
+        let closest = self.spatial_index.k_closest_to(pos, k);
+        for (_, index) in &closest {
+            let led = &mut self.leds[*index as usize];
+            led.color = color_rule(led);
+        }
+
+        !closest.is_empty()
+    }
+
+    /// Sets the color of the `k` [LEDs](Led) closest to a given point. See [Sled::k_closest_to()].
+    ///
+    /// Returns false if no LEDs were changed, which only happens when `k` is 0.
+    ///
+    /// O(NEARBY_LEDS * k)
+    pub fn set_k_closest_to(&mut self, pos: Vec2, k: usize, color: Color) -> bool {
+        self.modulate_k_closest_to(pos, k, |_| color)
+    }
+
     /* furthest getters/setters */
 
     /// Returns the index of the [LED](Led) furthest from a given point.
+    /// LEDs in a [gap](Led::is_gap) are never returned. If several LEDs are equally far, the lowest index wins.
     ///
-    /// O(VERTICES)
+    /// The furthest point on a straight segment is always one of its ends, so for layouts without curves only the LEDs
+    /// at the ends of each segment, or on either side of a gap, have to be checked. Otherwise, the Sled's grid of LEDs
+    /// is searched from the outside in, stopping once no remaining cell could hold anything further away.
+    ///
+    /// O(SEGMENTS + GAPS), or O(OUTLYING_LEDS) for layouts with curves.
     pub fn index_of_furthest_from(&self, pos: Vec2) -> usize {
-        // get the distance_squared of each vertex point, then find out which is the furthest.
-        let (index_of_furthest, _dist) = self
-            .vertex_indices
-            .iter()
-            .map(|i| {
-                let vertex_pos = self.leds[*i].position();
-                (*i, pos.distance_squared(vertex_pos))
-            })
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
-            .unwrap();
+        let Some(candidates) = &self.furthest_candidates else {
+            return self.spatial_index.furthest_from(pos).unwrap().1 as usize;
+        };
 
-        index_of_furthest
+        candidates
+            .iter()
+            .map(|i| (*i, pos.distance_squared(self.leds[*i].position())))
+            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap().then(b.0.cmp(&a.0)))
+            .unwrap()
+            .0
     }
 
     /// Returns the index of the [LED](Led) furthest from the center point.
@@ -140,7 +206,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Returns the [LED](Led) furthest from a given point.
     ///
-    /// O(VERTICES)
+    /// O(SEGMENTS + GAPS), or O(OUTLYING_LEDS) for layouts with curves.
     pub fn furthest_from(&self, pos: Vec2) -> &Led<Color> {
         let index_of_furthest = self.index_of_furthest_from(pos);
         &self.leds[index_of_furthest]
@@ -164,7 +230,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Modulates the color of the [LED](Led) furthest from a given point
     ///
-    /// O(SEGMENTS)
+    /// O(SEGMENTS + GAPS), or O(OUTLYING_LEDS) for layouts with curves.
     ///  
     ///```rust
     ///# use spatial_led::{Sled, SledError, Vec2};
@@ -195,7 +261,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Sets the color of the [LED](Led) furthest from a given point.
     ///
-    /// O(VERTICES)
+    /// O(SEGMENTS + GAPS), or O(OUTLYING_LEDS) for layouts with curves.
     pub fn set_furthest_from(&mut self, pos: Vec2, color: Color) {
         let index_of_furthest = self.index_of_furthest_from(pos);
         self.leds[index_of_furthest].color = color;
@@ -208,7 +274,9 @@ impl<Color: ColorType> Sled<Color> {
         for (segment_index, segment) in self.line_segments.iter().enumerate() {
             for alpha in segment.intersects_circle(pos, dist) {
                 let index = self.alpha_to_index(alpha, segment_index);
-                all_at_distance.push(index);
+                if !self.leds[index].is_gap() {
+                    all_at_distance.push(index);
+                }
             }
         }
 
@@ -225,7 +293,9 @@ impl<Color: ColorType> Sled<Color> {
         for (segment_index, segment) in self.line_segments.iter().enumerate() {
             for alpha in segment.intersects_circle(pos, dist) {
                 let index = self.alpha_to_index(alpha, segment_index);
-                all_at_distance.insert(index as u16);
+                if !self.leds[index].is_gap() {
+                    all_at_distance.insert(index as u32);
+                }
             }
         }
 
@@ -277,80 +347,601 @@ impl<Color: ColorType> Sled<Color> {
         self.within_dist_from(dist, self.center_point)
     }
 
+    /// Returns a [Filter] containing all LEDs less than `dist` away from `pos`.
+    ///
+    /// Only LEDs in the Sled's grid cells overlapping the circle are checked.
+    ///
+    /// O(NEARBY_LEDS)
     pub fn within_dist_from(&self, dist: f32, pos: Vec2) -> Filter {
-        let mut all_within_distance = BTreeSet::new();
-
         let target_sq = dist.powi(2);
+        self.filter_region(circle_bounds(pos, dist), |p| {
+            p.distance_squared(pos) < target_sq
+        })
+    }
 
-        for led in &self.leds {
-            if led.position().distance_squared(pos) < target_sq {
-                all_within_distance.insert(led.index());
-            }
-        }
+    pub fn modulate_within_dist<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        dist: f32,
+        color_rule: F,
+    ) -> bool {
+        self.modulate_within_dist_from(dist, self.center_point, color_rule)
+    }
 
-        all_within_distance.into()
+    pub fn set_within_dist(&mut self, dist: f32, color: Color) -> bool {
+        self.modulate_within_dist(dist, |_| color)
     }
 
-    pub fn modulate_within_dist<F: Fn(&Led<Color>) -> Color>(
+    pub fn modulate_within_dist_from<F: Fn(&Led<Color>) -> Color>(
         &mut self,
         dist: f32,
+        pos: Vec2,
         color_rule: F,
     ) -> bool {
-        let mut changes_made = false;
+        let target_sq = dist.powi(2);
+        self.modulate_region(
+            circle_bounds(pos, dist),
+            |p| p.distance_squared(pos) < target_sq,
+            color_rule,
+        )
+    }
 
-        for led in &mut self.leds {
-            if led.distance() < dist {
-                led.color = color_rule(led);
-                changes_made = true;
+    pub fn set_within_dist_from(&mut self, dist: f32, pos: Vec2, color: Color) -> bool {
+        self.modulate_within_dist_from(dist, pos, |_| color)
+    }
+
+    /* line methods */
+
+    /// Returns a [Filter] containing each [LED](Led) where the line segment from `a` to `b` crosses the layout.
+    /// Calculated by intersecting it with each line segment and finding the closest LED to each point of contact, like [Sled::at_dir()].
+    /// To sweep a line across the whole layout, pick `a` and `b` outside its [domain](Sled::domain).
+    ///
+    /// If the line doesn't cross any segments, or only crosses them in [gaps](Led::is_gap), the Filter will be empty.
+    ///
+    /// O(SEGMENTS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// // a scanner bar, currently crossing the room at x = 2.05
+    /// let bar = sled.at_line(Vec2::new(2.05, -1.0), Vec2::new(2.05, 5.0));
+    /// assert!(bar.into_iter().eq([20, 99]));
+    ///
+    /// sled.set_at_line(Vec2::new(2.05, -1.0), Vec2::new(2.05, 5.0), Rgb::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn at_line(&self, a: Vec2, b: Vec2) -> Filter {
+        self.indices_at_line(a, b)
+            .into_iter()
+            .map(|i| i as u32)
+            .collect::<BTreeSet<u32>>()
+            .into()
+    }
+
+    /// Modulates the color of each [LED](Led) where the line segment from `a` to `b` crosses the layout. See [Sled::at_line()].
+    ///
+    /// Returns false if the line doesn't cross any LEDs, true otherwise.
+    ///
+    /// O(SEGMENTS)
+    pub fn modulate_at_line<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        a: Vec2,
+        b: Vec2,
+        color_rule: F,
+    ) -> bool {
+        let indices = self.indices_at_line(a, b);
+        for index in &indices {
+            let led = &mut self.leds[*index];
+            led.color = color_rule(led);
+        }
+
+        !indices.is_empty()
+    }
+
+    /// Sets the color of each [LED](Led) where the line segment from `a` to `b` crosses the layout. See [Sled::at_line()].
+    ///
+    /// Returns false if the line doesn't cross any LEDs, true otherwise.
+    ///
+    /// O(SEGMENTS)
+    pub fn set_at_line(&mut self, a: Vec2, b: Vec2, color: Color) -> bool {
+        self.modulate_at_line(a, b, |_| color)
+    }
+
+    fn indices_at_line(&self, a: Vec2, b: Vec2) -> SmallVec<[usize; 4]> {
+        let mut indices = smallvec![];
+        for (segment_index, segment) in self.line_segments.iter().enumerate() {
+            for alpha in segment.intersects_line(a, b) {
+                let index = self.alpha_to_index(alpha, segment_index);
+                if !self.leds[index].is_gap() && !indices.contains(&index) {
+                    indices.push(index);
+                }
             }
         }
 
-        changes_made
+        indices
     }
 
-    pub fn set_within_dist(&mut self, dist: f32, color: Color) -> bool {
-        let mut changes_made = false;
+    /* region methods */
 
-        for led in &mut self.leds {
-            if led.distance() < dist {
-                led.color = color;
-                changes_made = true;
+    /// Returns a [Filter] containing all LEDs inside the given polygon, whose vertices are listed in order, in either direction.
+    /// The polygon is closed automatically and may be concave; if its edges cross, the even-odd rule decides what's inside.
+    /// Polygons with fewer than three vertices contain nothing. LEDs in a [gap](Led::is_gap) are never included.
+    ///
+    /// O(NEARBY_LEDS * POLYGON_VERTICES), where only LEDs in grid cells overlapping the polygon's bounding box are nearby.
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// // an L-shaped bar, tucked into the room's bottom-left corner
+    /// let bar = [
+    ///     Vec2::new(-1.0, -1.0),
+    ///     Vec2::new(3.05, -1.0),
+    ///     Vec2::new(3.05, 0.5),
+    ///     Vec2::new(0.5, 0.5),
+    ///     Vec2::new(0.5, 2.95),
+    ///     Vec2::new(-1.0, 2.95),
+    /// ];
+    /// assert_eq!(sled.within_polygon(&bar).len(), 60);
+    ///
+    /// sled.set_within_polygon(&bar, Rgb::new(1.0, 0.5, 0.0));
+    /// ```
+    pub fn within_polygon(&self, polygon: &[Vec2]) -> Filter {
+        match Rect::bounding(polygon) {
+            Some(bounds) if polygon.len() >= 3 => {
+                self.filter_region(bounds, |pos| polygon_contains(polygon, pos))
             }
+            _ => BTreeSet::new().into(),
         }
+    }
 
-        changes_made
+    /// Sets the color of all LEDs inside the given polygon. See [Sled::within_polygon()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS * POLYGON_VERTICES)
+    pub fn set_within_polygon(&mut self, polygon: &[Vec2], color: Color) -> bool {
+        self.modulate_within_polygon(polygon, |_| color)
     }
 
-    pub fn modulate_within_dist_from<F: Fn(&Led<Color>) -> Color>(
+    /// Modulates the color of each LED inside the given polygon. See [Sled::within_polygon()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS * POLYGON_VERTICES)
+    pub fn modulate_within_polygon<F: Fn(&Led<Color>) -> Color>(
         &mut self,
-        dist: f32,
-        pos: Vec2,
+        polygon: &[Vec2],
         color_rule: F,
     ) -> bool {
-        let target_sq = dist.powi(2);
-        let mut changes_made = false;
+        match Rect::bounding(polygon) {
+            Some(bounds) if polygon.len() >= 3 => {
+                self.modulate_region(bounds, |pos| polygon_contains(polygon, pos), color_rule)
+            }
+            _ => false,
+        }
+    }
 
-        for led in &mut self.leds {
-            if led.position().distance_squared(pos) < target_sq {
+    /// Maps each LED inside the given polygon to a color depending on its position. See [Sled::within_polygon()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS * POLYGON_VERTICES)
+    pub fn map_within_polygon(
+        &mut self,
+        polygon: &[Vec2],
+        pos_to_color_map: impl Fn(Vec2) -> Color,
+    ) -> bool {
+        self.modulate_within_polygon(polygon, |led| pos_to_color_map(led.position()))
+    }
+
+    /// Returns a [Filter] containing all LEDs inside the given rectangle, including those on its edges.
+    /// LEDs in a [gap](Led::is_gap) are never included.
+    ///
+    /// O(NEARBY_LEDS), where only LEDs in grid cells overlapping the rectangle are nearby.
+    /// ```rust
+    ///# use spatial_led::{Sled, Rect, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// let corner = Rect::new(Vec2::new(3.45, -0.5), Vec2::new(4.5, 1.05));
+    /// assert_eq!(sled.within_rect(corner).len(), 16);
+    ///
+    /// sled.map_within_rect(corner, |pos| Rgb::new(pos.y, 0.0, 0.0));
+    /// ```
+    pub fn within_rect(&self, rect: Rect) -> Filter {
+        self.filter_region(rect, |_| true)
+    }
+
+    /// Sets the color of all LEDs inside the given rectangle. See [Sled::within_rect()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn set_within_rect(&mut self, rect: Rect, color: Color) -> bool {
+        self.modulate_region(rect, |_| true, |_| color)
+    }
+
+    /// Modulates the color of each LED inside the given rectangle. See [Sled::within_rect()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn modulate_within_rect<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        rect: Rect,
+        color_rule: F,
+    ) -> bool {
+        self.modulate_region(rect, |_| true, color_rule)
+    }
+
+    /// Maps each LED inside the given rectangle to a color depending on its position. See [Sled::within_rect()].
+    /// Returns true if any LEDs were inside.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn map_within_rect(
+        &mut self,
+        rect: Rect,
+        pos_to_color_map: impl Fn(Vec2) -> Color,
+    ) -> bool {
+        self.modulate_region(rect, |_| true, |led| pos_to_color_map(led.position()))
+    }
+
+    /// Returns every lit LED inside `bounds` for which `inside` holds.
+    fn filter_region(&self, bounds: Rect, inside: impl Fn(Vec2) -> bool) -> Filter {
+        self.spatial_index
+            .candidates_in(bounds)
+            .filter(|(pos, _)| bounds.contains(*pos) && inside(*pos))
+            .map(|(_, index)| index)
+            .collect()
+    }
+
+    /// Recolors every lit LED inside `bounds` for which `inside` holds, returning true if there were any.
+    fn modulate_region(
+        &mut self,
+        bounds: Rect,
+        inside: impl Fn(Vec2) -> bool,
+        color_rule: impl Fn(&Led<Color>) -> Color,
+    ) -> bool {
+        let mut changes_made = false;
+        for (pos, index) in self.spatial_index.candidates_in(bounds) {
+            if bounds.contains(pos) && inside(pos) {
+                let led = &mut self.leds[index as usize];
                 led.color = color_rule(led);
-                changes_made = true
+                changes_made = true;
             }
         }
 
         changes_made
     }
 
-    pub fn set_within_dist_from(&mut self, dist: f32, pos: Vec2, color: Color) -> bool {
-        let target_sq = dist.powi(2);
-        let mut changes_made = false;
+    /* sector and annulus methods */
 
-        for led in &mut self.leds {
-            if led.position().distance_squared(pos) < target_sq {
-                led.color = color;
-                changes_made = true;
+    /// Returns a [Filter] containing all LEDs whose angle relative to the center point lies in the sector sweeping counter-clockwise from `start_angle` to `end_angle`, in radians.
+    ///
+    /// See [Sled::within_sector_from()] for details.
+    ///
+    /// O(LEDS)
+    pub fn within_sector(&self, start_angle: f32, end_angle: f32) -> Filter {
+        self.within_sector_from(self.center_point, start_angle, end_angle)
+    }
+
+    /// Returns a [Filter] containing all LEDs whose angle relative to `pos` lies in the sector sweeping counter-clockwise from `start_angle` to `end_angle`, in radians.
+    ///
+    /// Angles wrap around, so a sector from `3π/2` to `π/2` is the half facing +X, the same as one from `-π/2` to `π/2`.
+    /// Sweeps of a full turn or more cover every LED. LEDs on either edge of the sector are included.
+    ///
+    /// O(LEDS)
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// use core::f32::consts::{FRAC_PI_4, TAU};
+    ///
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (2.05, 2.05)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// // a radar beam, a quarter turn wide and crossing the +X axis
+    /// let beam = sled.within_sector(TAU - FRAC_PI_4, FRAC_PI_4);
+    /// assert_eq!(beam, sled.within_sector(-FRAC_PI_4, FRAC_PI_4));
+    ///
+    /// sled.set_within_sector(TAU - FRAC_PI_4, FRAC_PI_4, Rgb::new(0.0, 1.0, 0.0));
+    /// ```
+    pub fn within_sector_from(&self, pos: Vec2, start_angle: f32, end_angle: f32) -> Filter {
+        let sweep = sector_sweep(start_angle, end_angle);
+        self.filter_region(self.domain.clone().into(), |p| {
+            sector_contains(p - pos, start_angle, sweep)
+        })
+    }
+
+    /// Modulates the color of each [LED](Led) in the sector sweeping counter-clockwise from `start_angle` to `end_angle` around the center point.
+    /// See [Sled::within_sector_from()].
+    ///
+    /// Returns false if there were no LEDs in the sector, true otherwise.
+    ///
+    /// O(LEDS)
+    pub fn modulate_within_sector<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        start_angle: f32,
+        end_angle: f32,
+        color_rule: F,
+    ) -> bool {
+        self.modulate_within_sector_from(self.center_point, start_angle, end_angle, color_rule)
+    }
+
+    /// Modulates the color of each [LED](Led) in the sector sweeping counter-clockwise from `start_angle` to `end_angle` around `pos`.
+    /// See [Sled::within_sector_from()].
+    ///
+    /// Returns false if there were no LEDs in the sector, true otherwise.
+    ///
+    /// O(LEDS)
+    pub fn modulate_within_sector_from<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        pos: Vec2,
+        start_angle: f32,
+        end_angle: f32,
+        color_rule: F,
+    ) -> bool {
+        let sweep = sector_sweep(start_angle, end_angle);
+        self.modulate_region(
+            self.domain.clone().into(),
+            |p| sector_contains(p - pos, start_angle, sweep),
+            color_rule,
+        )
+    }
+
+    /// Sets the color of each [LED](Led) in the sector sweeping counter-clockwise from `start_angle` to `end_angle` around the center point.
+    /// See [Sled::within_sector_from()].
+    ///
+    /// Returns false if there were no LEDs in the sector, true otherwise.
+    ///
+    /// O(LEDS)
+    pub fn set_within_sector(&mut self, start_angle: f32, end_angle: f32, color: Color) -> bool {
+        self.modulate_within_sector(start_angle, end_angle, |_| color)
+    }
+
+    /// Sets the color of each [LED](Led) in the sector sweeping counter-clockwise from `start_angle` to `end_angle` around `pos`.
+    /// See [Sled::within_sector_from()].
+    ///
+    /// Returns false if there were no LEDs in the sector, true otherwise.
+    ///
+    /// O(LEDS)
+    pub fn set_within_sector_from(
+        &mut self,
+        pos: Vec2,
+        start_angle: f32,
+        end_angle: f32,
+        color: Color,
+    ) -> bool {
+        self.modulate_within_sector_from(pos, start_angle, end_angle, |_| color)
+    }
+
+    /// Returns a [Filter] containing all LEDs in the ring around the center point between `inner_radius` and `outer_radius`.
+    ///
+    /// See [Sled::within_annulus_from()] for details.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn within_annulus(&self, inner_radius: f32, outer_radius: f32) -> Filter {
+        self.within_annulus_from(self.center_point, inner_radius, outer_radius)
+    }
+
+    /// Returns a [Filter] containing all LEDs in the ring around `pos` between `inner_radius` and `outer_radius`.
+    ///
+    /// LEDs exactly `inner_radius` away are included and those exactly `outer_radius` away are not, so rings sharing a radius never overlap.
+    /// With an `inner_radius` of zero, this is the same as [Sled::within_dist_from()].
+    ///
+    /// O(NEARBY_LEDS), where only LEDs in grid cells overlapping the ring's bounding box are nearby.
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (2, 2)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)
+    /// ").unwrap();
+    ///
+    /// // a ripple travelling outwards from the corner at (0, 0)
+    /// let radius = 3.0;
+    /// let ripple = sled.within_annulus_from(Vec2::ZERO, radius - 0.25, radius + 0.25);
+    /// sled.set_filter(&ripple, Rgb::new(0.0, 0.0, 1.0));
+    /// ```
+    pub fn within_annulus_from(&self, pos: Vec2, inner_radius: f32, outer_radius: f32) -> Filter {
+        self.filter_region(circle_bounds(pos, outer_radius), |p| {
+            annulus_contains(p.distance_squared(pos), inner_radius, outer_radius)
+        })
+    }
+
+    /// Modulates the color of each [LED](Led) in the ring around the center point between `inner_radius` and `outer_radius`.
+    /// See [Sled::within_annulus_from()].
+    ///
+    /// Returns false if there were no LEDs in the ring, true otherwise.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn modulate_within_annulus<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        color_rule: F,
+    ) -> bool {
+        self.modulate_within_annulus_from(self.center_point, inner_radius, outer_radius, color_rule)
+    }
+
+    /// Modulates the color of each [LED](Led) in the ring around `pos` between `inner_radius` and `outer_radius`.
+    /// See [Sled::within_annulus_from()].
+    ///
+    /// Returns false if there were no LEDs in the ring, true otherwise.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn modulate_within_annulus_from<F: Fn(&Led<Color>) -> Color>(
+        &mut self,
+        pos: Vec2,
+        inner_radius: f32,
+        outer_radius: f32,
+        color_rule: F,
+    ) -> bool {
+        self.modulate_region(
+            circle_bounds(pos, outer_radius),
+            |p| annulus_contains(p.distance_squared(pos), inner_radius, outer_radius),
+            color_rule,
+        )
+    }
+
+    /// Sets the color of each [LED](Led) in the ring around the center point between `inner_radius` and `outer_radius`.
+    /// See [Sled::within_annulus_from()].
+    ///
+    /// Returns false if there were no LEDs in the ring, true otherwise.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn set_within_annulus(
+        &mut self,
+        inner_radius: f32,
+        outer_radius: f32,
+        color: Color,
+    ) -> bool {
+        self.modulate_within_annulus(inner_radius, outer_radius, |_| color)
+    }
+
+    /// Sets the color of each [LED](Led) in the ring around `pos` between `inner_radius` and `outer_radius`.
+    /// See [Sled::within_annulus_from()].
+    ///
+    /// Returns false if there were no LEDs in the ring, true otherwise.
+    ///
+    /// O(NEARBY_LEDS)
+    pub fn set_within_annulus_from(
+        &mut self,
+        pos: Vec2,
+        inner_radius: f32,
+        outer_radius: f32,
+        color: Color,
+    ) -> bool {
+        self.modulate_within_annulus_from(pos, inner_radius, outer_radius, |_| color)
+    }
+
+    /* color transfer */
+
+    /// Gives each [LED](Led) the color of the LED in `source` at the same position, such as when swapping in an edited layout.
+    /// LEDs match if they're less than half the spacing between two LEDs apart, going by the larger of the two layouts' densities.
+    /// LEDs without a match, and [gaps](Led::is_gap), keep their current color.
+    ///
+    /// O(LEDS * log(LEDS))
+    /// ```rust
+    ///# use spatial_led::{Sled, Vec2};
+    ///# use palette::rgb::Rgb;
+    /// let mut old = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 0) --> (1, 0)
+    /// ").unwrap();
+    /// old.set_all(Rgb::new(1.0, 0.0, 0.0));
+    ///
+    /// // the same strip, now with a second one wired in front of it
+    /// let mut new = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     (0, 1) --> (1, 1) | (0, 0) --> (1, 0)
+    /// ").unwrap();
+    /// new.copy_colors_by_position(&old);
+    ///
+    /// assert_eq!(new.get(0).unwrap().color, Rgb::new(0.0, 0.0, 0.0));
+    /// assert_eq!(new.get(10).unwrap().color, Rgb::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn copy_colors_by_position(&mut self, source: &Sled<Color>) {
+        let tolerance = 0.5 / self.density.max(source.density);
+        let cell_of = |p: Vec2| {
+            (
+                (p.x / tolerance).floor() as i64,
+                (p.y / tolerance).floor() as i64,
+            )
+        };
+
+        // bucket the source's LEDs into cells as wide as the tolerance,
+        // so any match for a given LED must be in its own cell or a neighboring one.
+        let mut cells: BTreeMap<(i64, i64), Vec<&Led<Color>>> = BTreeMap::new();
+        for led in source.live_leds() {
+            cells.entry(cell_of(led.position())).or_default().push(led);
+        }
+
+        for led in self.leds.iter_mut().filter(|led| !led.is_gap()) {
+            let pos = led.position();
+            let (x, y) = cell_of(pos);
+            let closest = (x - 1..=x + 1)
+                .flat_map(|x| (y - 1..=y + 1).map(move |y| (x, y)))
+                .filter_map(|cell| cells.get(&cell))
+                .flatten()
+                .map(|other| (other, other.position().distance_squared(pos)))
+                .filter(|(_, dist_sq)| *dist_sq < tolerance * tolerance)
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+
+            if let Some((other, _)) = closest {
+                led.color = other.color;
+            }
+        }
+    }
+}
+
+/// Returns true if `point` is inside `polygon`, by the even-odd rule: a ray cast from the point crosses the polygon's edges an odd number of times.
+fn polygon_contains(polygon: &[Vec2], point: Vec2) -> bool {
+    let mut inside = false;
+    let mut previous = polygon[polygon.len() - 1];
+    for &vertex in polygon {
+        // only edges straddling the point's height can cross a horizontal ray.
+        if (vertex.y > point.y) != (previous.y > point.y) {
+            let t = (point.y - vertex.y) / (previous.y - vertex.y);
+            if point.x < vertex.x + t * (previous.x - vertex.x) {
+                inside = !inside;
             }
         }
+        previous = vertex;
+    }
+    inside
+}
 
-        changes_made
+/// Returns how far a sector from `start_angle` to `end_angle` sweeps counter-clockwise, wrapped into `0..TAU`, or `TAU` if it's a full turn or more.
+fn sector_sweep(start_angle: f32, end_angle: f32) -> f32 {
+    let sweep = end_angle - start_angle;
+    if sweep >= TAU {
+        TAU
+    } else {
+        wrap_angle(sweep)
     }
 }
+
+/// Returns true if the direction of `offset` lies within `sweep` radians counter-clockwise of `start_angle`.
+fn sector_contains(offset: Vec2, start_angle: f32, sweep: f32) -> bool {
+    sweep >= TAU || wrap_angle(offset.y.atan2(offset.x) - start_angle) <= sweep
+}
+
+/// Wraps an angle in radians into `0..TAU`.
+fn wrap_angle(angle: f32) -> f32 {
+    let wrapped = angle % TAU;
+    if wrapped < 0.0 {
+        wrapped + TAU
+    } else {
+        wrapped
+    }
+}
+
+/// Returns the square enclosing a circle of the given radius.
+fn circle_bounds(pos: Vec2, radius: f32) -> Rect {
+    Rect::from_center_size(pos, Vec2::splat(radius * 2.0))
+}
+
+/// Returns true if a point `dist_sq` squared units away is in the ring between the two radii.
+fn annulus_contains(dist_sq: f32, inner_radius: f32, outer_radius: f32) -> bool {
+    dist_sq >= inner_radius.max(0.0).powi(2) && dist_sq < outer_radius.powi(2)
+}
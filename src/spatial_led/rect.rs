@@ -0,0 +1,62 @@
+use core::ops::Range;
+
+use crate::Vec2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// An axis-aligned rectangle, used to pick out rectangular regions of a [Sled](crate::Sled) with methods like [Sled::within_rect()](crate::Sled::within_rect).
+///
+/// Points on the edges count as inside.
+/// ```rust
+/// # use spatial_led::{Rect, Vec2};
+/// let bar = Rect::new(Vec2::new(2.0, 1.0), Vec2::new(-1.0, 0.0));
+///
+/// assert_eq!(bar.min, Vec2::new(-1.0, 0.0));
+/// assert!(bar.contains(Vec2::new(0.5, 1.0)));
+/// assert!(!bar.contains(Vec2::new(0.5, 1.5)));
+/// ```
+pub struct Rect {
+    pub min: Vec2,
+    pub max: Vec2,
+}
+
+impl Rect {
+    /// Creates the rectangle spanning between two opposite corners, given in any order.
+    pub fn new(corner: Vec2, opposite_corner: Vec2) -> Self {
+        Rect {
+            min: corner.min(opposite_corner),
+            max: corner.max(opposite_corner),
+        }
+    }
+
+    /// Creates a rectangle of the given width and height, centered on `center`.
+    pub fn from_center_size(center: Vec2, size: Vec2) -> Self {
+        let half_size = size.abs() * 0.5;
+        Rect::new(center - half_size, center + half_size)
+    }
+
+    /// Returns the smallest rectangle containing every given point, or `None` if there aren't any.
+    pub(crate) fn bounding(points: &[Vec2]) -> Option<Self> {
+        let (first, rest) = points.split_first()?;
+        Some(rest.iter().fold(Rect::new(*first, *first), |rect, point| {
+            Rect::new(rect.min.min(*point), rect.max.max(*point))
+        }))
+    }
+
+    /// Returns true if `point` lies inside the rectangle, or on its edge.
+    pub fn contains(&self, point: Vec2) -> bool {
+        point.cmpge(self.min).all() && point.cmple(self.max).all()
+    }
+
+    /// Returns true if the two rectangles overlap, including if they only touch.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        self.min.cmple(other.max).all() && other.min.cmple(self.max).all()
+    }
+}
+
+/// Converts a bounding box like the one returned by [Sled::domain()](crate::Sled::domain).
+impl From<Range<Vec2>> for Rect {
+    fn from(value: Range<Vec2>) -> Self {
+        Rect::new(value.start, value.end)
+    }
+}
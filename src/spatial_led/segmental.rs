@@ -1,8 +1,8 @@
 use core::ops::Range;
 
-use alloc::collections::BTreeSet;
+use alloc::collections::{BTreeMap, BTreeSet};
 use alloc::format;
-use alloc::string::ToString;
+use alloc::string::{String, ToString};
 
 use crate::{
     color::ColorType,
@@ -17,9 +17,15 @@ impl<Color: ColorType> Sled<Color> {
     ///
     /// O(LEDS_IN_SEGMENT)
     pub fn segment(&self, segment_index: usize) -> Option<Filter> {
-        let (start, end) = *self.line_segment_endpoint_indices.get(segment_index)?;
-        let led_range = &self.leds[start..end];
-        Some(led_range.into())
+        if segment_index >= self.line_segment_endpoint_indices.len() {
+            return None;
+        }
+
+        let indices: BTreeSet<u32> = self
+            .path_range(segment_index, segment_index)
+            .map(|i| self.path_to_physical[i] as u32)
+            .collect();
+        Some(indices.into())
     }
     /// Modulates the color of each [LED](Led) assigned to the line segment with index `segment_index` given a color rule function. Returns an [error](SledError) if there is no line segment with the given index.
     ///
@@ -47,8 +53,8 @@ impl<Color: ColorType> Sled<Color> {
             .as_err();
         }
 
-        let (start, end) = self.line_segment_endpoint_indices[segment_index];
-        for led in &mut self.leds[start..end] {
+        for i in self.path_range(segment_index, segment_index) {
+            let led = &mut self.leds[self.path_to_physical[i]];
             led.color = color_rule(led);
         }
 
@@ -68,8 +74,9 @@ impl<Color: ColorType> Sled<Color> {
             .as_err();
         }
 
-        let (start, end) = self.line_segment_endpoint_indices[segment_index];
-        self.set_range(start..end, color)?;
+        for i in self.path_range(segment_index, segment_index) {
+            self.leds[self.path_to_physical[i]].color = color;
+        }
         Ok(())
     }
 
@@ -90,13 +97,16 @@ impl<Color: ColorType> Sled<Color> {
     ///# }
     /// ```
     pub fn segments(&self, range: Range<usize>) -> Option<Filter> {
-        if range.start >= self.line_segment_endpoint_indices.len() {
+        if range.start >= self.line_segment_endpoint_indices.len()
+            || range.end >= self.line_segment_endpoint_indices.len()
+        {
             None
         } else {
-            let (start, _) = *self.line_segment_endpoint_indices.get(range.start)?;
-            let (_, end) = *self.line_segment_endpoint_indices.get(range.end)?;
-            let led_range = &self.leds[start..end];
-            Some(led_range.into())
+            let indices: BTreeSet<u32> = self
+                .path_range(range.start, range.end)
+                .map(|i| self.path_to_physical[i] as u32)
+                .collect();
+            Some(indices.into())
         }
     }
 
@@ -127,9 +137,8 @@ impl<Color: ColorType> Sled<Color> {
             .as_err();
         }
 
-        let (start, _) = self.line_segment_endpoint_indices[range.start];
-        let (_, end) = self.line_segment_endpoint_indices[range.end];
-        for led in &mut self.leds[start..end] {
+        for i in self.path_range(range.start, range.end) {
+            let led = &mut self.leds[self.path_to_physical[i]];
             led.color = color_rule(led);
         }
         Ok(())
@@ -148,10 +157,8 @@ impl<Color: ColorType> Sled<Color> {
             .as_err();
         }
 
-        let (start, _) = self.line_segment_endpoint_indices[range.start];
-        let (_, end) = self.line_segment_endpoint_indices[range.end];
-        for led in &mut self.leds[start..end] {
-            led.color = color;
+        for i in self.path_range(range.start, range.end) {
+            self.leds[self.path_to_physical[i]].color = color;
         }
         Ok(())
     }
@@ -178,9 +185,11 @@ impl<Color: ColorType> Sled<Color> {
         mut func: F,
     ) -> Result<(), SledError> {
         if segment_index >= self.line_segment_endpoint_indices.len() {
-            return Err(SledError {
-                message: format!("No line segment of index {} exists.", segment_index),
-            });
+            return SledError::new(format!(
+                "No line segment of index {} exists.",
+                segment_index
+            ))
+            .as_err();
         }
 
         let (start, end) = self.line_segment_endpoint_indices[segment_index];
@@ -188,7 +197,7 @@ impl<Color: ColorType> Sled<Color> {
 
         for index in start..end {
             let alpha = (index - start) as f32 / num_leds_f32;
-            func(&mut self.leds[index], alpha);
+            func(&mut self.leds[self.path_to_physical[index]], alpha);
         }
 
         Ok(())
@@ -207,7 +216,7 @@ impl<Color: ColorType> Sled<Color> {
             return None;
         }
 
-        Some(&self.leds[vertex_index])
+        Some(&self.leds[self.vertex_indices[vertex_index]])
     }
     /// Modulates the color of the [LED](Led) that represents the vertex the given index, if it exists. Returns an [error](SledError) if not.
     /// Vertices are distinct from line segement endpoints in that line segments with touching endpoints will share a vertex.
@@ -236,7 +245,7 @@ impl<Color: ColorType> Sled<Color> {
                 .as_err();
         }
 
-        let led = &mut self.leds[vertex_index];
+        let led = &mut self.leds[self.vertex_indices[vertex_index]];
         led.color = color_rule(led);
         Ok(())
     }
@@ -263,7 +272,7 @@ impl<Color: ColorType> Sled<Color> {
 
     /// Returns a [Filter] containing all vertices in the system.
     pub fn vertices(&self) -> Filter {
-        let hs: BTreeSet<u16> = self.vertex_indices.iter().map(|i| *i as u16).collect();
+        let hs: BTreeSet<u32> = self.vertex_indices.iter().map(|i| *i as u32).collect();
         hs.into()
     }
 
@@ -308,3 +317,223 @@ impl<Color: ColorType> Sled<Color> {
         }
     }
 }
+
+/// # Name-based read methods.
+impl<Color: ColorType> Sled<Color> {
+    /// Returns the set of all [LEDs](Led) assigned to the line segment labelled `name`,
+    /// so that edits to the config that renumber segments don't change which LEDs are meant.
+    ///
+    /// Segments are labelled with a `name` attribute after their `-->`. Chains and vertices can be named too; see [Sled::chain_by_name()] and [Sled::vertex_by_name()].
+    /// Names must be unique across every chain, segment and vertex in the layout.
+    ///
+    /// Returns an [error](SledError) if no segment has the given name.
+    ///
+    /// O(SEGMENTS + LEDS_IN_SEGMENT)
+    ///
+    /// ```rust
+    ///# use spatial_led::{Sled, SledError, Vec2};
+    /// use palette::rgb::Rgb;
+    ///# fn main() -> Result<(), SledError> {
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     [name: room] (0, 0) [name: door] --> (2, 0) [name: corner]
+    ///     --> [name: north_wall] (2, 2) --> (0, 2)
+    /// ")?;
+    ///
+    /// let north_wall = sled.segment_by_name("north_wall")?;
+    /// assert_eq!(north_wall.len(), 20);
+    /// sled.set_filter(&north_wall, Rgb::new(1.0, 0.0, 0.0));
+    ///
+    /// assert_eq!(sled.vertex_by_name("corner")?.position(), Vec2::new(2.0, 0.0));
+    /// assert_eq!(sled.chain_by_name("room")?.len(), 60);
+    /// assert!(sled.segment_by_name("south_wall").is_err());
+    ///# Ok(())
+    ///# }
+    /// ```
+    pub fn segment_by_name(&self, name: &str) -> Result<Filter, SledError> {
+        match self.segment_index_by_name(name) {
+            Some(segment_index) => Ok(self.segment(segment_index).unwrap()),
+            None => SledError::new(format!("No segment named `{}` exists.", name)).as_err(),
+        }
+    }
+
+    /// Returns the set of all [LEDs](Led) assigned to the chain labelled `name`, i.e. with a `name` attribute at its start.
+    ///
+    /// Returns an [error](SledError) if no chain has the given name.
+    ///
+    /// O(CHAINS + LEDS_IN_CHAIN)
+    pub fn chain_by_name(&self, name: &str) -> Result<Filter, SledError> {
+        let chain = self
+            .chains
+            .iter()
+            .find(|chain| chain.name.as_deref() == Some(name));
+
+        match chain {
+            Some(chain) => Ok(self
+                .path_range(chain.segments.start, chain.segments.end - 1)
+                .map(|i| self.path_to_physical[i] as u32)
+                .collect()),
+            None => SledError::new(format!("No chain named `{}` exists.", name)).as_err(),
+        }
+    }
+
+    /// Returns the set of all [LEDs](Led) in the group `name`, i.e. every chain that came from the layout file [included](Sled::new) under that name.
+    ///
+    /// Groups from nested includes are named by their path, like `venue/kitchen`, and asking for `venue` includes them too.
+    ///
+    /// Returns an [error](SledError) if no chain is in the given group.
+    ///
+    /// O(CHAINS + LEDS_IN_GROUP)
+    pub fn group(&self, name: &str) -> Result<Filter, SledError> {
+        let in_group = |group: &str| {
+            group == name
+                || group
+                    .strip_prefix(name)
+                    .is_some_and(|rest| rest.starts_with('/'))
+        };
+
+        let mut chains = self
+            .chains
+            .iter()
+            .filter(|chain| chain.group.as_deref().is_some_and(in_group))
+            .peekable();
+        if chains.peek().is_none() {
+            return SledError::new(format!("No group named `{}` exists.", name)).as_err();
+        }
+
+        Ok(chains
+            .flat_map(|chain| self.path_range(chain.segments.start, chain.segments.end - 1))
+            .map(|i| self.path_to_physical[i] as u32)
+            .collect())
+    }
+
+    /// Returns the name of each group in the layout, in the order they first appear. See [Sled::group()].
+    ///
+    /// O(CHAINS)
+    pub fn groups(&self) -> impl Iterator<Item = &str> {
+        let mut seen = BTreeSet::new();
+        self.chains
+            .iter()
+            .filter_map(|chain| chain.group.as_deref())
+            .filter(move |group| seen.insert(*group))
+    }
+
+    /// Returns the [LED](Led) that represents the vertex labelled `name`, i.e. followed by a `[name: ...]` list.
+    ///
+    /// Returns an [error](SledError) if no vertex has the given name.
+    ///
+    /// O(SEGMENTS)
+    pub fn vertex_by_name(&self, name: &str) -> Result<&Led<Color>, SledError> {
+        let is_named = |label: &Option<String>| label.as_deref() == Some(name);
+
+        let path_index = if let Some(chain) = self.chains.iter().find(|c| is_named(&c.start_name)) {
+            self.line_segment_endpoint_indices[chain.segments.start].0
+        } else if let Some(segment_index) = self
+            .line_segments
+            .iter()
+            .position(|segment| is_named(&segment.end_name))
+        {
+            self.line_segment_endpoint_indices[segment_index].1 - 1
+        } else {
+            return SledError::new(format!("No vertex named `{}` exists.", name)).as_err();
+        };
+
+        Ok(&self.leds[self.path_to_physical[path_index]])
+    }
+
+    /// Returns the [LEDs](Led) labelled `name`, whether that's a chain, a segment, or a single vertex.
+    ///
+    /// Returns an [error](SledError) if nothing in the layout has the given name.
+    ///
+    /// O(SEGMENTS + LEDS_IN_RESULT)
+    pub fn named(&self, name: &str) -> Result<Filter, SledError> {
+        if let Ok(segment) = self.segment_by_name(name) {
+            return Ok(segment);
+        }
+        if let Ok(chain) = self.chain_by_name(name) {
+            return Ok(chain);
+        }
+        if let Ok(vertex) = self.vertex_by_name(name) {
+            return Ok([vertex.index()].into_iter().collect());
+        }
+
+        SledError::new(format!("Nothing named `{}` exists in the layout.", name)).as_err()
+    }
+
+    fn segment_index_by_name(&self, name: &str) -> Option<usize> {
+        self.line_segments
+            .iter()
+            .position(|segment| segment.name.as_deref() == Some(name))
+    }
+}
+
+/// # Tag-based read methods.
+impl<Color: ColorType> Sled<Color> {
+    /// Returns the tags attached to the segment the [LED](Led) at `index` belongs to, or `None` if no LED at `index` exists.
+    ///
+    /// Segments are tagged with a `tags` attribute after their `-->`, or at the start of a chain to tag every segment in it.
+    /// Each tag is either `key=value` or a bare `key`, which is given the value `true`.
+    ///
+    /// O(log(TAGS))
+    ///
+    /// ```rust
+    ///# use spatial_led::{Sled, SledError};
+    /// use palette::rgb::Rgb;
+    ///# fn main() -> Result<(), SledError> {
+    /// let mut sled = Sled::<Rgb>::new_from_str("
+    ///     center: (0, 0)
+    ///     density: 10
+    ///     --segments--
+    ///     [tags: zone=bar] (0, 0) --> [tags: facing=down diffused] (1, 0) --> (1, 1) |
+    ///     (2, 0) --> [tags: zone=stage] (3, 0)
+    /// ")?;
+    ///
+    /// let tags = sled.tags_of(5).unwrap();
+    /// assert_eq!(tags["zone"], "bar");
+    /// assert_eq!(tags["facing"], "down");
+    /// assert_eq!(tags["diffused"], "true");
+    ///
+    /// let bar = sled.filter_by_tag("zone", "bar");
+    /// assert_eq!(bar.len(), 20);
+    /// sled.set_filter(&bar, Rgb::new(1.0, 0.5, 0.0));
+    ///# Ok(())
+    ///# }
+    /// ```
+    pub fn tags_of(&self, index: usize) -> Option<&BTreeMap<String, String>> {
+        let led = self.leds.get(index)?;
+        Some(&self.line_segments[led.segment() as usize].tags)
+    }
+
+    /// Returns the set of all [LEDs](Led) on segments tagged `key=value`. A bare tag like `diffused` has the value `true`.
+    ///
+    /// O(SEGMENTS + LEDS_IN_RESULT)
+    ///
+    /// ```rust
+    ///# use spatial_led::{Sled, SledBuilder, Chain, Segment, Vec2};
+    /// use palette::rgb::Rgb;
+    /// let sled: Sled<Rgb> = SledBuilder::new(Vec2::ZERO, 10.0)
+    ///     .chain(
+    ///         Chain::new()
+    ///             .tag("zone", "bar")
+    ///             .vertex(Vec2::new(0.0, 0.0))
+    ///             .vertex_with(Vec2::new(1.0, 0.0), Segment::line().tag("zone", "stage"))
+    ///             .vertex(Vec2::new(1.0, 1.0)),
+    ///     )
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// assert!(sled.filter_by_tag("zone", "stage").into_iter().eq(0..10));
+    /// assert!(sled.filter_by_tag("zone", "bar").into_iter().eq(10..20));
+    /// ```
+    pub fn filter_by_tag(&self, key: &str, value: &str) -> Filter {
+        self.line_segments
+            .iter()
+            .enumerate()
+            .filter(|(_, segment)| segment.tags.get(key).map(String::as_str) == Some(value))
+            .flat_map(|(segment_index, _)| self.path_range(segment_index, segment_index))
+            .map(|i| self.path_to_physical[i] as u32)
+            .collect()
+    }
+}
@@ -0,0 +1,70 @@
+use alloc::vec::Vec;
+
+use serde::{de::Error as _, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{color::ColorType, spatial_led::Sled, SledBuilder};
+
+// The layout is stored as a SledBuilder rather than as raw LEDs, so that
+// deserializing goes through the same validation as any other constructor
+// and all of the cached lookup tables are rebuilt.
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Sled")]
+struct SledData<COLOR> {
+    layout: SledBuilder,
+    #[serde(default)]
+    colors: Vec<COLOR>,
+}
+
+/// Serializes the Sled's layout alongside the color of each LED.
+///
+/// ```rust
+///# #[cfg(feature = "serde")] {
+///# use spatial_led::Sled;
+///# use palette::rgb::Rgb;
+/// let mut sled = Sled::<(u8, u8, u8)>::new("./benches/config.yap").unwrap();
+/// sled.set_segment(2, (255, 0, 0)).unwrap();
+///
+/// let json = serde_json::to_string(&sled).unwrap();
+/// let restored: Sled<(u8, u8, u8)> = serde_json::from_str(&json).unwrap();
+///
+/// assert!(restored.positions().eq(sled.positions()));
+/// assert!(restored.colors().eq(sled.colors()));
+///# }
+/// ```
+impl<COLOR: ColorType + Serialize> Serialize for Sled<COLOR> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        SledData {
+            layout: self.to_builder(),
+            colors: self.colors().copied().collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+/// Rebuilds a Sled from its layout and restores each LED's color.
+///
+/// `colors` may be omitted, in which case every LED starts with `COLOR::default()`.
+/// Otherwise, it must contain exactly one color per LED.
+impl<'de, COLOR: ColorType + Deserialize<'de>> Deserialize<'de> for Sled<COLOR> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let data = SledData::<COLOR>::deserialize(deserializer)?;
+        let mut sled: Sled<COLOR> = data.layout.build().map_err(D::Error::custom)?;
+
+        if data.colors.is_empty() {
+            return Ok(sled);
+        }
+
+        if data.colors.len() != sled.num_leds {
+            return Err(D::Error::invalid_length(
+                data.colors.len(),
+                &"one color for each LED in the layout",
+            ));
+        }
+
+        for (led, color) in sled.leds.iter_mut().zip(data.colors) {
+            led.color = color;
+        }
+
+        Ok(sled)
+    }
+}
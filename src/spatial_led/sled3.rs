@@ -0,0 +1,511 @@
+use core::ops::Range;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{
+    color::ColorType,
+    config::Config,
+    error::SledError,
+    led::Led3,
+    spatial_led::{Filter, Sled},
+    SledBuilder, Vec3,
+};
+
+#[derive(Clone, Debug)]
+/// A struct representing the layout of some LED configuration in 3D space, for fixtures that aren't flat,
+/// like a tree wrapped in lights or strips running up the corners of a room. Requires the `3d` feature.
+///
+/// Sled3s are described by the same [config files](Sled::new) as a [Sled], except that any vertex may be written
+/// as `(x, y, z)`, with `z` defaulting to 0. Segments rise or fall linearly between the heights of their vertices,
+/// LEDs are spaced evenly along them in 3D, and `density` counts that climb.
+/// Arcs and Bézier curves are still drawn in the `x, y` plane, so an arc whose ends are at different heights makes a helix.
+/// The `center` may be given a height too.
+///
+/// Distances, directions and the spatial queries built on them are measured in 3D. Queries that follow the layout's path,
+/// like those based on angles, aren't available; use [Sled3::layout()] for those, along with the index-, segment-, name- and tag-based lookups,
+/// which work as they do for any [Sled]. LED indices are shared between the two.
+///
+/// Planar configs work unchanged, with every LED at `z = 0`, and 3D configs can still be loaded into a [Sled], which ignores heights.
+/// ```rust
+/// # use spatial_led::{Sled3, Vec3};
+/// # use palette::rgb::Rgb;
+/// let mut sled = Sled3::<Rgb>::new_from_str("
+///     center: (0, 0, 1)
+///     density: 10
+///     --segments--
+///     (0, 0, 0) --> (0, 0, 2) |              # a pole, straight up
+///     (1, 0, 0) --> [arc: (-1, 0)] (1, 0, 2) # wound once around it
+/// ").unwrap();
+///
+/// assert_eq!(sled.segment(0).unwrap().len(), 20);
+/// assert!(sled.get(19).unwrap().position().abs_diff_eq(Vec3::new(0.0, 0.0, 2.0), 1e-5));
+/// assert_eq!(sled.within_dist(0.25).len(), 5);
+/// assert_eq!(sled.closest_to(Vec3::new(0.0, 0.0, 3.0)).index(), 19);
+///
+/// sled.map_by_pos(|pos| Rgb::new(pos.z / 2.0, 0.0, 0.0));
+/// ```
+pub struct Sled3<COLOR: ColorType> {
+    // the layout seen from above. Segments, wiring, names and tags all live here.
+    layout: Sled<()>,
+    center_point: Vec3,
+    leds: Vec<Led3<COLOR>>,
+    index_of_closest: usize,
+    index_of_furthest: usize,
+    domain: Range<Vec3>,
+    // what gap LEDs output, regardless of the color they've been set to
+    gap_color: COLOR,
+}
+
+/// # Construction, output, and basic sled info
+impl<COLOR: ColorType> Sled3<COLOR> {
+    /// Constructs a new Sled3 given the path to a config file. See [Sled3] for how heights are written.
+    #[cfg(feature = "std")]
+    pub fn new(config_file_path: &str) -> Result<Self, SledError> {
+        let config = Config::from_toml_file(config_file_path)?;
+        Ok(Sled3::from_layout(Sled::new_from_config(config)?))
+    }
+
+    /// Works like [Sled3::new()] but rather than reading the contents of a config file from disk, allows you to pass in the same information as a `&str`.
+    pub fn new_from_str(string: &str) -> Result<Self, SledError> {
+        let config = Config::from_str(string)?;
+        Ok(Sled3::from_layout(Sled::new_from_config(config)?))
+    }
+
+    /// Constructs a Sled3 from a [SledBuilder], using [Chain::vertex_z()](crate::Chain::vertex_z) and [SledBuilder::center_z()] for heights.
+    ///
+    /// ```rust
+    /// # use spatial_led::{Sled3, SledBuilder, Chain, Vec2, Vec3};
+    /// # use palette::rgb::Rgb;
+    /// let sled = Sled3::<Rgb>::from_builder(
+    ///     SledBuilder::new(Vec2::ZERO, 10.0)
+    ///         .center_z(1.0)
+    ///         .chain(Chain::new().vertex(Vec2::ZERO).vertex(Vec2::ZERO).vertex_z(2.0)),
+    /// )
+    /// .unwrap();
+    ///
+    /// assert_eq!(sled.num_leds(), 20);
+    /// assert_eq!(sled.center_point(), Vec3::new(0.0, 0.0, 1.0));
+    /// ```
+    pub fn from_builder(builder: SledBuilder) -> Result<Self, SledError> {
+        Ok(Sled3::from_layout(builder.build()?))
+    }
+
+    fn from_layout(layout: Sled<()>) -> Self {
+        let center_point = layout.center_point.extend(layout.center_z);
+
+        // heights are worked out along the drawn path, then stored in the order LEDs are wired.
+        let mut heights = vec![0.0; layout.num_leds];
+        let mut path_index = 0;
+        for segment in &layout.line_segments {
+            for led in 0..segment.num_leds() {
+                heights[layout.path_to_physical[path_index]] = segment.led_z(led);
+                path_index += 1;
+            }
+        }
+
+        let leds = layout
+            .leds
+            .iter()
+            .zip(heights)
+            .map(|(led, z)| {
+                Led3::new(
+                    COLOR::default(),
+                    led.position().extend(z),
+                    led.index(),
+                    led.segment(),
+                    center_point,
+                    led.is_gap(),
+                )
+            })
+            .collect();
+
+        let mut sled = Sled3 {
+            layout,
+            center_point,
+            leds,
+            index_of_closest: 0,
+            index_of_furthest: 0,
+            domain: Vec3::ZERO..Vec3::ZERO,
+            gap_color: COLOR::default(),
+        };
+        sled.domain = sled.calc_domain();
+        sled.set_center_point(center_point);
+        sled
+    }
+
+    /// Writes the Sled3's layout back out in the [config file](Sled::new) format, heights included. See [Sled::to_config_string()].
+    ///
+    /// O(SEGMENTS)
+    pub fn to_config_string(&self) -> String {
+        self.layout.to_config_string()
+    }
+
+    /// Returns the layout as seen from above, with every LED's height dropped. Its LEDs share their indices with this Sled3's.
+    ///
+    /// Useful for everything that doesn't depend on height, like looking segments up by [name](Sled::segment_by_name) or [tag](Sled::filter_by_tag),
+    /// or finding [outputs](Sled::outputs).
+    ///
+    /// O(1)
+    pub fn layout(&self) -> &Sled<()> {
+        &self.layout
+    }
+
+    /// Returns a read-only iterator over the system's [LEDs](Led3).
+    ///
+    /// O(LEDS)
+    pub fn leds(&self) -> impl Iterator<Item = &Led3<COLOR>> {
+        self.leds.iter()
+    }
+
+    /// Returns an Iterator over the colors for each [LED](Led3) in the system.
+    /// LEDs in a [gap](Led3::is_gap) always output `COLOR::default()`, whatever they've been set to.
+    ///
+    /// O(LEDS)
+    pub fn colors(&self) -> impl Iterator<Item = &COLOR> + '_ {
+        self.leds.iter().map(|led| self.output_color(led))
+    }
+
+    /// Returns an Iterator over Vec3s, representing the position of each [LED](Led3) in the system.
+    ///
+    /// O(LEDS)
+    pub fn positions(&self) -> impl Iterator<Item = Vec3> + '_ {
+        self.leds.iter().map(|led| led.position())
+    }
+
+    /// Returns an Iterator over tuple pairs of the color and position of each [LED](Led3) in the system.
+    /// Like [Sled3::colors()], LEDs in a gap always output `COLOR::default()`.
+    ///
+    /// O(LEDS)
+    pub fn colors_and_positions(&self) -> impl Iterator<Item = (COLOR, Vec3)> + '_ {
+        self.leds
+            .iter()
+            .map(|led| (*self.output_color(led), led.position()))
+    }
+
+    /// Returns the static reference point declared in the [config file](Sled::new).
+    ///
+    /// O(1)
+    pub fn center_point(&self) -> Vec3 {
+        self.center_point
+    }
+
+    /// Moves the reference point that [directions](Led3::direction), [distances](Led3::distance) and the
+    /// `_from`-less spatial methods are measured from. Colors are kept. See [Sled::set_center_point()].
+    ///
+    /// O(LEDS)
+    pub fn set_center_point(&mut self, center_point: Vec3) {
+        self.center_point = center_point;
+        for led in &mut self.leds {
+            led.set_center_point(center_point);
+        }
+
+        let by_distance =
+            |a: &&Led3<COLOR>, b: &&Led3<COLOR>| a.distance().total_cmp(&b.distance());
+        self.index_of_closest = self.live_leds().min_by(by_distance).unwrap().index() as usize;
+        self.index_of_furthest = self
+            .live_leds()
+            .max_by(|a, b| by_distance(a, b).then(b.index().cmp(&a.index())))
+            .unwrap()
+            .index() as usize;
+    }
+
+    /// Returns the total number of [LEDs](Led3) in the system.
+    ///
+    /// O(1)
+    pub fn num_leds(&self) -> usize {
+        self.leds.len()
+    }
+
+    /// Returns the total number of line segments in the system.
+    ///
+    /// O(1)
+    pub fn num_segments(&self) -> usize {
+        self.layout.num_segments()
+    }
+
+    /// Returns a bounding box around the LEDs where the minimum x, y and z
+    /// position is [Range::start], maximum x, y and z is [Range::end].
+    ///
+    /// O(1)
+    pub fn domain(&self) -> Range<Vec3> {
+        self.domain.clone()
+    }
+
+    fn calc_domain(&self) -> Range<Vec3> {
+        self.live_leds().fold(Vec3::MAX..Vec3::MIN, |domain, led| {
+            domain.start.min(led.position())..domain.end.max(led.position())
+        })
+    }
+
+    fn live_leds(&self) -> impl Iterator<Item = &Led3<COLOR>> {
+        self.leds.iter().filter(|led| !led.is_gap())
+    }
+
+    fn output_color<'a>(&'a self, led: &'a Led3<COLOR>) -> &'a COLOR {
+        if led.is_gap() {
+            &self.gap_color
+        } else {
+            &led.color
+        }
+    }
+}
+
+/// # Index and segment-based read and write methods.
+impl<COLOR: ColorType> Sled3<COLOR> {
+    /// Returns `Some(&Led3<COLOR>)` if an [LED](Led3) at `index` exists, `None` if not.
+    ///
+    /// O(1)
+    pub fn get(&self, index: usize) -> Option<&Led3<COLOR>> {
+        self.leds.get(index)
+    }
+
+    /// Set the color of the [LED](Led3) at `index` to `color`.
+    /// Returns an [error](SledError) if no LED exists at that index.
+    ///
+    /// O(1)
+    pub fn set(&mut self, index: usize, color: COLOR) -> Result<(), SledError> {
+        match self.leds.get_mut(index) {
+            Some(led) => {
+                led.color = color;
+                Ok(())
+            }
+            None => SledError::new(format!("LED at index {} does not exist.", index)).as_err(),
+        }
+    }
+
+    /// Sets the color of all [LEDs](Led3) in the system to `color`.
+    ///
+    /// O(LEDS)
+    pub fn set_all(&mut self, color: COLOR) {
+        for led in &mut self.leds {
+            led.color = color;
+        }
+    }
+
+    /// Returns a [Filter] containing each [LED](Led3) in the given segment, or `None` if it doesn't exist. See [Sled::segment()].
+    ///
+    /// O(LEDS_IN_SEGMENT)
+    pub fn segment(&self, segment_index: usize) -> Option<Filter> {
+        self.layout.segment(segment_index)
+    }
+
+    /// Returns the [LED](Led3) that represents the vertex at `vertex_index`, or `None` if it doesn't exist. See [Sled::vertex()].
+    ///
+    /// O(1)
+    pub fn vertex(&self, vertex_index: usize) -> Option<&Led3<COLOR>> {
+        let index = self.layout.vertex(vertex_index)?.index();
+        self.leds.get(index as usize)
+    }
+
+    /// Returns a [Filter] containing each [LED](Led3) that represents a vertex.
+    ///
+    /// O(VERTICES)
+    pub fn vertices(&self) -> Filter {
+        self.layout.vertices()
+    }
+
+    /// Returns a [Filter] containing each [LED](Led3) that sits in a [gap](Led3::is_gap).
+    ///
+    /// O(LEDS)
+    pub fn gaps(&self) -> Filter {
+        self.layout.gaps()
+    }
+}
+
+/// # Filter-based write methods.
+impl<COLOR: ColorType> Sled3<COLOR> {
+    /// Sets all LEDs in the given filter to `color`.
+    ///
+    /// O(LEDS_IN_FILTER)
+    pub fn set_filter(&mut self, filter: &Filter, color: COLOR) {
+        for i in filter {
+            self.leds[i as usize].color = color;
+        }
+    }
+
+    /// Modulates the color of each LED contained in the filter.
+    ///
+    /// O(LEDS_IN_FILTER)
+    pub fn modulate_filter<F: Fn(&Led3<COLOR>) -> COLOR>(
+        &mut self,
+        filter: &Filter,
+        color_rule: F,
+    ) {
+        for i in filter {
+            let led = &mut self.leds[i as usize];
+            led.color = color_rule(led)
+        }
+    }
+
+    /// For-each method granting mutable access to each LED contained in the given filter.
+    ///
+    /// O(LEDS_IN_FILTER)
+    pub fn for_each_in_filter<F: FnMut(&mut Led3<COLOR>)>(&mut self, filter: &Filter, mut func: F) {
+        for i in filter {
+            func(&mut self.leds[i as usize]);
+        }
+    }
+}
+
+/// # Maps and filters
+impl<COLOR: ColorType> Sled3<COLOR> {
+    /// Maps LEDs to a color.
+    ///
+    /// O(LEDS)
+    pub fn map(&mut self, led_to_color_map: impl Fn(&Led3<COLOR>) -> COLOR) {
+        self.leds
+            .iter_mut()
+            .for_each(|led| led.color = led_to_color_map(led));
+    }
+
+    /// Maps LEDs to a color depending on their position.
+    ///
+    /// O(LEDS)
+    pub fn map_by_pos(&mut self, pos_to_color_map: impl Fn(Vec3) -> COLOR) {
+        self.map(|led| pos_to_color_map(led.position()));
+    }
+
+    /// Maps LEDs to a color depending on their direction from the `center_point`.
+    ///
+    /// O(LEDS)
+    pub fn map_by_dir(&mut self, dir_to_color_map: impl Fn(Vec3) -> COLOR) {
+        self.map(|led| dir_to_color_map(led.direction()));
+    }
+
+    /// Maps LEDs to a color depending on their direction from the given point.
+    ///
+    /// O(LEDS)
+    pub fn map_by_dir_from(&mut self, pos: Vec3, dir_to_color_map: impl Fn(Vec3) -> COLOR) {
+        self.map(|led| dir_to_color_map((led.position() - pos).normalize_or_zero()));
+    }
+
+    /// Maps LEDs to a color depending on their distance from the `center_point`.
+    ///
+    /// O(LEDS)
+    pub fn map_by_dist(&mut self, dist_to_color_map: impl Fn(f32) -> COLOR) {
+        self.map(|led| dist_to_color_map(led.distance()));
+    }
+
+    /// Maps LEDs to a color depending on their distance from the given point.
+    ///
+    /// O(LEDS)
+    pub fn map_by_dist_from(&mut self, pos: Vec3, dist_to_color_map: impl Fn(f32) -> COLOR) {
+        self.map(|led| dist_to_color_map(pos.distance(led.position())));
+    }
+
+    /// Returns a [Filter] containing all LEDs that meet a certain criteria.
+    /// LEDs in a [gap](Led3::is_gap) are never included.
+    ///
+    /// O(LEDS)
+    pub fn filter(&self, filter: impl Fn(&Led3<COLOR>) -> bool) -> Filter {
+        self.live_leds()
+            .filter(|led| filter(led))
+            .map(|led| led.index())
+            .collect()
+    }
+
+    /// Returns a [Filter] containing all LEDs whose position meets a certain criteria.
+    ///
+    /// O(LEDS)
+    pub fn filter_by_pos(&self, pos_filter: impl Fn(Vec3) -> bool) -> Filter {
+        self.filter(|led| pos_filter(led.position()))
+    }
+
+    /// Returns a [Filter] containing all LEDs whose direction from the `center_point` meets a certain criteria.
+    ///
+    /// O(LEDS)
+    pub fn filter_by_dir(&self, dir_filter: impl Fn(Vec3) -> bool) -> Filter {
+        self.filter(|led| dir_filter(led.direction()))
+    }
+
+    /// Returns a [Filter] containing all LEDs whose distance from the `center_point` meets a certain criteria.
+    ///
+    /// O(LEDS)
+    pub fn filter_by_dist(&self, dist_filter: impl Fn(f32) -> bool) -> Filter {
+        self.filter(|led| dist_filter(led.distance()))
+    }
+
+    /// Returns a [Filter] containing all LEDs whose distance from the given point meets a certain criteria.
+    ///
+    /// O(LEDS)
+    pub fn filter_by_dist_from(&self, pos: Vec3, dist_filter: impl Fn(f32) -> bool) -> Filter {
+        self.filter(|led| dist_filter(pos.distance(led.position())))
+    }
+}
+
+/// # Position-based read and write methods.
+impl<COLOR: ColorType> Sled3<COLOR> {
+    /// Returns the [LED](Led3) closest to the center point.
+    ///
+    /// O(1)
+    pub fn closest(&self) -> &Led3<COLOR> {
+        &self.leds[self.index_of_closest]
+    }
+
+    /// Returns the [LED](Led3) closest to a given point.
+    ///
+    /// O(LEDS)
+    pub fn closest_to(&self, pos: Vec3) -> &Led3<COLOR> {
+        self.live_leds()
+            .min_by(|a, b| {
+                let a = a.position().distance_squared(pos);
+                let b = b.position().distance_squared(pos);
+                a.total_cmp(&b)
+            })
+            .unwrap()
+    }
+
+    /// Returns the [LED](Led3) furthest from the center point.
+    ///
+    /// O(1)
+    pub fn furthest(&self) -> &Led3<COLOR> {
+        &self.leds[self.index_of_furthest]
+    }
+
+    /// Returns the [LED](Led3) furthest from a given point.
+    ///
+    /// O(LEDS)
+    pub fn furthest_from(&self, pos: Vec3) -> &Led3<COLOR> {
+        self.live_leds()
+            .max_by(|a, b| {
+                let dist_a = a.position().distance_squared(pos);
+                let dist_b = b.position().distance_squared(pos);
+                dist_a.total_cmp(&dist_b).then(b.index().cmp(&a.index()))
+            })
+            .unwrap()
+    }
+
+    /// Returns a [Filter] containing all LEDs within `dist` of the center point.
+    ///
+    /// O(LEDS)
+    pub fn within_dist(&self, dist: f32) -> Filter {
+        self.within_dist_from(dist, self.center_point)
+    }
+
+    /// Returns a [Filter] containing all LEDs within `dist` of the given point.
+    ///
+    /// O(LEDS)
+    pub fn within_dist_from(&self, dist: f32, pos: Vec3) -> Filter {
+        let target_sq = dist.powi(2);
+        self.filter(|led| led.position().distance_squared(pos) < target_sq)
+    }
+
+    /// Sets the color of all LEDs within `dist` of the center point.
+    ///
+    /// O(LEDS)
+    pub fn set_within_dist(&mut self, dist: f32, color: COLOR) {
+        let filter = self.within_dist(dist);
+        self.set_filter(&filter, color);
+    }
+
+    /// Sets the color of all LEDs within `dist` of the given point.
+    ///
+    /// O(LEDS)
+    pub fn set_within_dist_from(&mut self, dist: f32, pos: Vec3, color: COLOR) {
+        let filter = self.within_dist_from(dist, pos);
+        self.set_filter(&filter, color);
+    }
+}
@@ -0,0 +1,262 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use crate::{spatial_led::Rect, Vec2};
+
+#[cfg(not(feature = "std"))]
+use num_traits::float::Float as _;
+
+// roughly how many LEDs each cell should hold on average.
+const LEDS_PER_CELL: f32 = 2.0;
+
+/// A uniform grid over the positions of a Sled's lit LEDs, built once at construction
+/// so that positional queries only have to look at LEDs near the point in question.
+///
+/// Cells are stored back to back: the LEDs in cell `c` are `entries[starts[c]..starts[c + 1]]`.
+#[derive(Clone, Debug)]
+pub(crate) struct SpatialIndex {
+    origin: Vec2,
+    cell_size: f32,
+    columns: usize,
+    rows: usize,
+    starts: Vec<usize>,
+    entries: Vec<(Vec2, u32)>,
+}
+
+impl SpatialIndex {
+    /// Indexes the given `(position, index)` pairs, which should cover every LED that isn't in a gap.
+    ///
+    /// O(LEDS)
+    pub fn new(points: Vec<(Vec2, u32)>) -> Self {
+        let bounds = Rect::bounding(&points.iter().map(|(pos, _)| *pos).collect::<Vec<_>>())
+            .unwrap_or(Rect::new(Vec2::ZERO, Vec2::ZERO));
+        let size = bounds.max - bounds.min;
+        let count = points.len().max(1) as f32;
+
+        // aim for a couple of LEDs per cell across the layout's area, but since LEDs tend
+        // to sit along lines, don't let a thin layout be cut into more cells than it has LEDs.
+        let by_area = (size.x * size.y * LEDS_PER_CELL / count).sqrt();
+        let by_length = size.max_element() * LEDS_PER_CELL / count;
+        let cell_size = match by_area.max(by_length) {
+            size if size.is_finite() && size > 0.0 => size,
+            _ => 1.0,
+        };
+
+        let columns = (size.x / cell_size) as usize + 1;
+        let rows = (size.y / cell_size) as usize + 1;
+
+        let mut index = SpatialIndex {
+            origin: bounds.min,
+            cell_size,
+            columns,
+            rows,
+            starts: vec![0; columns * rows + 1],
+            entries: Vec::with_capacity(points.len()),
+        };
+
+        // counting sort the points by cell.
+        let cells: Vec<usize> = points
+            .iter()
+            .map(|(pos, _)| {
+                let (x, y) = index.clamped_cell_of(*pos);
+                index.cell_at(x, y)
+            })
+            .collect();
+        for cell in &cells {
+            index.starts[cell + 1] += 1;
+        }
+        for cell in 0..columns * rows {
+            index.starts[cell + 1] += index.starts[cell];
+        }
+
+        let mut next = index.starts.clone();
+        index.entries.resize(points.len(), (Vec2::ZERO, 0));
+        for (point, cell) in points.into_iter().zip(cells) {
+            index.entries[next[cell]] = point;
+            next[cell] += 1;
+        }
+
+        index
+    }
+
+    /// Returns every indexed LED in a cell overlapping `rect`, some of which may lie outside it.
+    pub fn candidates_in(&self, rect: Rect) -> impl Iterator<Item = (Vec2, u32)> + '_ {
+        let (min_x, min_y) = self.clamped_cell_of(rect.min);
+        let (max_x, max_y) = self.clamped_cell_of(rect.max);
+        let overlaps = rect.intersects(&self.bounds());
+
+        (min_y..=max_y)
+            .filter(move |_| overlaps)
+            .flat_map(move |y| self.cells_in_row(y, min_x, max_x))
+            .copied()
+    }
+
+    /// Returns the `k` indexed LEDs closest to `pos`, closest first.
+    /// Equally distant LEDs are ordered by index.
+    pub fn k_closest_to(&self, pos: Vec2, k: usize) -> Vec<(f32, u32)> {
+        let mut closest: Vec<(f32, u32)> = Vec::with_capacity(k.min(self.entries.len()) + 1);
+        if k == 0 {
+            return closest;
+        }
+
+        // search outwards one ring of cells at a time, starting from the cell containing `pos`
+        // or, if it lies outside the grid, the first ring that reaches into it.
+        let (x, y) = self.cell_of(pos);
+        for ring in self.rings_around(x, y) {
+            if closest.len() == k {
+                let bound = self.dist_beyond_rings(pos, x, y, ring - 1);
+                // LEDs exactly at the bound may still win a tie by index.
+                if closest[k - 1].0 < bound * bound {
+                    break;
+                }
+            }
+
+            self.visit_ring(x, y, ring, |cell| {
+                for (position, index) in cell {
+                    let candidate = (position.distance_squared(pos), *index);
+                    if closest.len() == k && !closer(candidate, closest[k - 1]) {
+                        continue;
+                    }
+
+                    let at = closest
+                        .iter()
+                        .position(|other| closer(candidate, *other))
+                        .unwrap_or(closest.len());
+                    closest.insert(at, candidate);
+                    closest.truncate(k);
+                }
+            });
+        }
+
+        closest
+    }
+
+    /// Returns the indexed LED furthest from `pos`, if there are any.
+    /// Equally distant LEDs are decided by lowest index.
+    pub fn furthest_from(&self, pos: Vec2) -> Option<(f32, u32)> {
+        let mut furthest: Option<(f32, u32)> = None;
+
+        // search inwards from the outermost ring of cells.
+        let (x, y) = self.cell_of(pos);
+        for ring in self.rings_around(x, y).rev() {
+            let reach = self.reach_within_rings(pos, x, y, ring);
+            if furthest.is_some_and(|(dist_sq, _)| dist_sq > reach * reach) {
+                break;
+            }
+
+            self.visit_ring(x, y, ring, |cell| {
+                for (position, index) in cell {
+                    let candidate = (position.distance_squared(pos), *index);
+                    let further = |(dist_sq, index): (f32, u32)| {
+                        candidate.0 > dist_sq || (candidate.0 == dist_sq && candidate.1 < index)
+                    };
+                    if furthest.map_or(true, further) {
+                        furthest = Some(candidate);
+                    }
+                }
+            });
+        }
+
+        furthest
+    }
+
+    /// Returns the range of rings around cell `(x, y)` that overlap the grid.
+    fn rings_around(&self, x: i64, y: i64) -> RangeInclusive<i64> {
+        let (last_column, last_row) = (self.columns as i64 - 1, self.rows as i64 - 1);
+        let nearest = (-x).max(x - last_column).max(-y).max(y - last_row).max(0);
+        let furthest = x.max(last_column - x).max(y).max(last_row - y);
+        nearest..=furthest
+    }
+
+    /// Returns how close any LED outside rings `0..=ring` around cell `(x, y)` could be to `pos`.
+    fn dist_beyond_rings(&self, pos: Vec2, x: i64, y: i64, ring: i64) -> f32 {
+        let low = self.origin + Vec2::new((x - ring) as f32, (y - ring) as f32) * self.cell_size;
+        let high = low + Vec2::splat((2 * ring + 1) as f32 * self.cell_size);
+
+        // sides of the searched block that reach past the edge of the grid have nothing beyond them.
+        let side = |covers_grid: bool, dist: f32| if covers_grid { f32::INFINITY } else { dist };
+        side(x - ring <= 0, pos.x - low.x)
+            .min(side(x + ring >= self.columns as i64 - 1, high.x - pos.x))
+            .min(side(y - ring <= 0, pos.y - low.y))
+            .min(side(y + ring >= self.rows as i64 - 1, high.y - pos.y))
+    }
+
+    /// Returns how far any LED inside rings `0..=ring` around cell `(x, y)` could be from `pos`.
+    fn reach_within_rings(&self, pos: Vec2, x: i64, y: i64, ring: i64) -> f32 {
+        let (last_column, last_row) = (self.columns as i64 - 1, self.rows as i64 - 1);
+        let first = Vec2::new((x - ring).max(0) as f32, (y - ring).max(0) as f32);
+        let last = Vec2::new(
+            (x + ring).min(last_column) as f32,
+            (y + ring).min(last_row) as f32,
+        );
+
+        // the corner of the searched block, trimmed to the grid, furthest from `pos`.
+        let low = self.origin + first * self.cell_size;
+        let high = self.origin + (last + Vec2::ONE) * self.cell_size;
+        (pos - low).abs().max((high - pos).abs()).length()
+    }
+
+    /// Calls `visit` with the contents of each row of cells making up the given ring around cell `(x, y)`.
+    fn visit_ring(&self, x: i64, y: i64, ring: i64, mut visit: impl FnMut(&[(Vec2, u32)])) {
+        let (last_column, last_row) = (self.columns as i64 - 1, self.rows as i64 - 1);
+        let (left, right) = ((x - ring).max(0), (x + ring).min(last_column));
+
+        for row in (y - ring).max(0)..=(y + ring).min(last_row) {
+            if (row - y).abs() == ring {
+                // the ring's top and bottom edges are visited in full,
+                if left <= right {
+                    visit(self.cells_in_row(row as usize, left as usize, right as usize));
+                }
+            } else {
+                // the rows in between only at its left and right ends.
+                for column in [x - ring, x + ring] {
+                    if (0..=last_column).contains(&column) {
+                        visit(self.cells_in_row(row as usize, column as usize, column as usize));
+                    }
+                }
+            }
+        }
+    }
+
+    fn cells_in_row(&self, row: usize, from: usize, to: usize) -> &[(Vec2, u32)] {
+        let start = self.starts[self.cell_at(from, row)];
+        let end = self.starts[self.cell_at(to, row) + 1];
+        &self.entries[start..end]
+    }
+
+    fn bounds(&self) -> Rect {
+        let size = Vec2::new(self.columns as f32, self.rows as f32) * self.cell_size;
+        Rect::new(self.origin, self.origin + size)
+    }
+
+    fn cell_at(&self, x: usize, y: usize) -> usize {
+        y * self.columns + x
+    }
+
+    /// Returns the cell containing `pos`, which may lie outside the grid.
+    ///
+    /// Points further than `columns + rows` cells out are pulled in to that distance, which keeps
+    /// ring arithmetic in range; the searches only rely on which side of the grid `pos` is on.
+    fn cell_of(&self, pos: Vec2) -> (i64, i64) {
+        let margin = (self.columns + self.rows) as f32;
+        let cell = ((pos - self.origin) / self.cell_size).floor();
+        (
+            cell.x.clamp(-margin, self.columns as f32 + margin) as i64,
+            cell.y.clamp(-margin, self.rows as f32 + margin) as i64,
+        )
+    }
+
+    fn clamped_cell_of(&self, pos: Vec2) -> (usize, usize) {
+        let (x, y) = self.cell_of(pos);
+        (
+            x.clamp(0, self.columns as i64 - 1) as usize,
+            y.clamp(0, self.rows as i64 - 1) as usize,
+        )
+    }
+}
+
+/// Orders `(distance_squared, index)` pairs by distance, then by index.
+fn closer(a: (f32, u32), b: (f32, u32)) -> bool {
+    a.0 < b.0 || (a.0 == b.0 && a.1 < b.1)
+}
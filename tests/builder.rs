@@ -0,0 +1,75 @@
+use spatial_led::{LintKind, LintSeverity, SledBuilder};
+
+fn lints(segments: &str) -> Vec<(LintKind, LintSeverity, Vec<usize>)> {
+    format!("center: (0, 0)\ndensity: 30\n--segments--\n{}", segments)
+        .parse::<SledBuilder>()
+        .unwrap()
+        .lint()
+        .unwrap()
+        .into_iter()
+        .map(|lint| (lint.kind, lint.severity(), lint.segments))
+        .collect()
+}
+
+#[test]
+fn short_segments() {
+    let found: Vec<_> =
+        lints("(0, 0) --> (0.01, 0) --> [leds: 3] (0.01, 0) --> (0.5, 0) --> (0.7, 0)")
+            .into_iter()
+            .map(|(kind, severity, _)| (kind, severity))
+            .collect();
+    assert_eq!(
+        found,
+        [
+            (LintKind::EmptySegment, LintSeverity::Error),
+            (LintKind::ZeroLength, LintSeverity::Warning)
+        ]
+    );
+
+    let err = "center: (0, 0)\ndensity: 30\n--segments--\n(0, 0) --> (0.01, 0)"
+        .parse::<SledBuilder>()
+        .unwrap()
+        .build::<(u8, u8, u8)>()
+        .unwrap_err();
+    assert_eq!(
+        err.message,
+        "Segment 0 has no LEDs; it is too short for its density."
+    );
+}
+
+#[test]
+fn overlapping_curves() {
+    let found: Vec<_> = lints(
+        "(0, 0) --> [arc: (1, 0)] (0, 0) |
+        (0, 0) --> [arc: (1, 0)] (0, 0) |
+        (3, 0) --> (4, 0) --> (3, 0) |
+        (5, 0) --> [bezier: (6, 1)] (7, 0) |
+        (7, 0) --> [bezier: (6, 1)] (5, 0)",
+    )
+    .into_iter()
+    .map(|(kind, _, segments)| (kind, segments))
+    .collect();
+    assert_eq!(
+        found,
+        [
+            (LintKind::Overlapping, vec![0, 1]),
+            (LintKind::Overlapping, vec![2, 3]),
+            (LintKind::Overlapping, vec![4, 5])
+        ]
+    );
+}
+
+#[test]
+fn tolerances_follow_each_segments_spacing() {
+    // the default density would miss the first pair, and flag the second.
+    let found: Vec<_> = lints(
+        "(0, 0) --> [leds: 2] (1, 0) |
+        (1.2, 0) --> (2, 0) |
+        (3, 0) --> [density: 300] (4, 0) |
+        (4.01, 0) --> [density: 300] (5, 0)",
+    )
+    .into_iter()
+    .map(|(kind, _, segments)| (kind, segments))
+    .collect();
+    assert_eq!(found, [(LintKind::NearlyConnected, vec![0, 1])]);
+}
@@ -0,0 +1,429 @@
+use palette::rgb::Rgb;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use spatial_led::{Affine2, Chain, ConfigErrorKind, Segment, Sled, SledBuilder, Vec2};
+
+fn sled(config: &str) -> Sled<Rgb> {
+    Sled::new_from_str(config).unwrap()
+}
+
+fn rejected_at(config: &str) -> (ConfigErrorKind, usize, usize) {
+    let err = Sled::<Rgb>::new_from_str(config).unwrap_err();
+    let config_err = err.config_error().unwrap();
+    (config_err.kind, config_err.line, config_err.column)
+}
+
+fn round_trips(sled: &Sled<Rgb>) -> bool {
+    let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    reloaded.positions().eq(sled.positions())
+}
+
+#[test]
+fn comments_are_ignored() {
+    let commented = sled(
+        "
+        # living room, measured from the doorway
+        center: (0.0, 0.5) # eye level
+        density: 30.0
+        --segments-- # clockwise from the left wall
+        (-2, 0) --> (0.5, -1) # under the window
+        --> (3.5, 0) | # break for the door
+        (2, # door frame
+         2) --> (-2, 2)",
+    );
+    let plain = sled(
+        "center: (0.0, 0.5)\ndensity: 30.0\n--segments--\n(-2, 0) --> (0.5, -1) --> (3.5, 0) | (2, 2) --> (-2, 2)",
+    );
+    assert!(commented.positions().eq(plain.positions()));
+}
+
+#[test]
+fn pinned_led_counts() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 30
+        --segments--
+        [density: 60] (0, 0) --> (1, 0) --> [leds: 47] (1, 1) |
+        (2, 0) --> (3, 0)",
+    );
+    assert_eq!(sled.closest_to(Vec2::new(1.1, 0.99)).index(), 106);
+}
+
+#[test]
+fn curves() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 30
+        --segments--
+        (1, 0) --> [arc: (-1, 0)] (1, 0) |
+        (-2, -2) --> [bezier: (0, -1), leds: 40] (2, -2)",
+    );
+    assert_eq!(sled.at_dir(Vec2::new(0.0, 1.0)).len(), 1);
+    assert_eq!(sled.at_dir(Vec2::new(0.0, -1.0)).len(), 2);
+}
+
+#[test]
+fn config_strings_round_trip() {
+    let mut rng = StdRng::seed_from_u64(12);
+    for _ in 0..50 {
+        let mut v = || Vec2::new(rng.gen_range(-10.0..10.0), rng.gen_range(-10.0..10.0));
+        let mut text = format!(
+            "center: ({}, {})\ndensity: 7.3\n--segments--\n",
+            v().x,
+            v().y
+        );
+        for chain in 0..3 {
+            let p = v();
+            let wiring = if chain % 2 == 0 {
+                ", reversed"
+            } else {
+                ", output: b"
+            };
+            text += &format!(
+                "[density: 1.{chain}{wiring}, offset: {}] ({}, {})",
+                chain * 3,
+                p.x,
+                p.y
+            );
+            for segment in 0..4 {
+                let (end, a, b) = (v(), v(), v());
+                let attributes = match segment {
+                    0 if chain == 1 => "[gap]".to_string(),
+                    0 if chain == 2 => format!("[points: ({}, {}) ({}, {})]", a.x, a.y, b.x, b.y),
+                    0 => String::new(),
+                    1 => format!("[leds: {}, bezier: ({}, {}), gap: 0]", chain + 1, a.x, a.y),
+                    2 => format!("[bezier: ({}, {}) ({}, {})]", a.x, a.y, b.x, b.y),
+                    _ => format!("[arc: ({}, {}), density: 3.7]", a.x, a.y),
+                };
+                text += &format!(" --> {attributes} ({}, {})", end.x, end.y);
+            }
+            text += " |\n";
+        }
+
+        let sled = sled(text.trim_end().trim_end_matches('|'));
+        let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+        assert_eq!(reloaded.to_config_string(), sled.to_config_string());
+        assert_eq!(reloaded.center_point(), sled.center_point());
+        assert!(reloaded.positions().eq(sled.positions()));
+        assert!(reloaded
+            .leds()
+            .map(|led| led.segment())
+            .eq(sled.leds().map(|led| led.segment())));
+        assert!(sled
+            .to_builder()
+            .build::<Rgb>()
+            .unwrap()
+            .positions()
+            .eq(sled.positions()));
+        assert!(reloaded.outputs().eq(sled.outputs()));
+        assert!(reloaded.gaps().into_iter().eq(sled.gaps()));
+    }
+}
+
+#[test]
+fn reversed_and_offset_wiring() {
+    let mut sled = sled(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [reversed] (0, 0) --> (1, 0) |
+        [offset: 5] (0, 1) --> (1, 1)",
+    );
+    assert!(sled.segment(0).unwrap().into_iter().eq(0..10));
+    assert!(sled
+        .leds()
+        .enumerate()
+        .all(|(i, led)| led.index() as usize == i));
+    assert_eq!(sled.vertex(0).unwrap().index(), 9);
+
+    // alpha follows the segment as drawn, not as wired.
+    sled.for_each_in_segment(1, |led, alpha| led.color = Rgb::new(alpha, 0.0, 0.0))
+        .unwrap();
+    assert_eq!(sled.get(15).unwrap().color, Rgb::new(0.0, 0.0, 0.0));
+    assert_eq!(sled.get(10).unwrap().color, Rgb::new(0.5, 0.0, 0.0));
+}
+
+#[test]
+fn gaps_are_left_out_of_spatial_queries() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        (0, 0) --> [gap: 0..2 9] (1, 0)
+        --> [gap] (1, 1)
+        --> (0, 1)",
+    );
+    assert!(sled.get(0).unwrap().is_gap());
+    assert!(!sled.get(2).unwrap().is_gap());
+    assert!(sled
+        .filter(|_| true)
+        .into_iter()
+        .all(|i| !sled.get(i as usize).unwrap().is_gap()));
+    assert!(!sled.furthest_from(Vec2::new(0.0, 0.0)).is_gap());
+    assert!(!sled.closest().is_gap());
+    assert!(sled.at_dir(Vec2::new(1.0, 0.5)).is_empty());
+}
+
+#[test]
+fn invalid_gaps() {
+    // a layout with nothing but gaps
+    let err = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [gap] (1, 0)",
+    )
+    .unwrap_err();
+    assert!(err.config_error().is_none());
+    assert_eq!(err.to_string(), "Every LED in the layout is inside a gap.");
+    // past the end of the segment
+    assert_eq!(
+        rejected_at("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [gap: 8..11] (1, 0)"),
+        (ConfigErrorKind::InvalidNumber, 4, 18)
+    );
+    // empty range
+    assert_eq!(
+        rejected_at("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [gap: 3..3] (1, 0)"),
+        (ConfigErrorKind::InvalidNumber, 4, 18)
+    );
+}
+
+#[test]
+fn measured_points() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        (0, 0) --> (1, 0) --> [points: (1.1, 0.3) (0.9, 0.5) (1.2, 0.8)] (1, 1)",
+    );
+    assert_eq!(sled.closest_to(Vec2::new(1.3, 0.8)).index(), 12);
+
+    assert_eq!(
+        rejected_at("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [points: (1, 1), leds: 2] (1, 0)"),
+        (ConfigErrorKind::DuplicateKey, 4, 29)
+    );
+    assert_eq!(
+        rejected_at("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [points] (1, 0)"),
+        (ConfigErrorKind::UnexpectedToken, 4, 13)
+    );
+}
+
+#[test]
+fn points() {
+    let positions = [
+        Vec2::new(0.1, 0.2),
+        Vec2::new(0.9, 0.3),
+        Vec2::new(0.4, 1.1),
+        Vec2::new(-0.6, 0.8),
+    ];
+    let sled = Sled::<Rgb>::new_from_points(Vec2::new(0.0, 0.5), &positions).unwrap();
+
+    assert_eq!(sled.closest_to(Vec2::new(0.1, 0.2)).index(), 0);
+    assert_eq!(sled.closest_to(Vec2::new(-0.6, 0.8)).index(), 3);
+    assert_eq!(sled.closest_to(Vec2::new(0.75, 0.25)).index(), 1);
+    assert_eq!(sled.furthest_from(Vec2::new(0.1, 0.2)).index(), 2);
+    assert_eq!(sled.at_dir(Vec2::new(1.0, 0.0)).len(), 1);
+
+    let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    assert!(reloaded.positions().eq(positions));
+
+    assert!(Sled::<Rgb>::new_from_points(Vec2::ZERO, &[]).is_err());
+    let single = Sled::<Rgb>::new_from_points(Vec2::ZERO, &[Vec2::ONE]).unwrap();
+    assert_eq!(single.closest().position(), Vec2::ONE);
+}
+
+#[test]
+fn csv() {
+    let unlabelled = Sled::<Rgb>::new_from_csv(Vec2::ZERO, "1, 2\n3, 4").unwrap();
+    assert_eq!(unlabelled.num_segments(), 1);
+
+    let err = Sled::<Rgb>::new_from_csv(Vec2::ZERO, "x, y\n1, 2\n3, four").unwrap_err();
+    let config_err = err.config_error().unwrap();
+    assert_eq!(
+        (
+            config_err.line,
+            config_err.column,
+            config_err.found.as_str()
+        ),
+        (3, 4, "four")
+    );
+
+    let err = Sled::<Rgb>::new_from_csv(Vec2::ZERO, "1, 2, a, b").unwrap_err();
+    assert_eq!(err.config_error().unwrap().column, 10);
+
+    assert!(Sled::<Rgb>::new_from_csv(Vec2::ZERO, "1").is_err());
+    assert!(Sled::<Rgb>::new_from_csv(Vec2::ZERO, "x, y\n").is_err());
+}
+
+#[test]
+fn transform_keys() {
+    let rotated = sled("center: (0, 0)\nscale: 2\nrotation: 90\ndensity: 10\n--segments--\n(0, 0) --> [arc: (0.5, 0.5)] (1, 0)");
+    let last = rotated.segment(0).unwrap().into_iter().max().unwrap();
+    assert!(rotated
+        .get(last as usize)
+        .unwrap()
+        .position()
+        .abs_diff_eq(Vec2::new(0.0, 2.0), 1e-5));
+    assert!(round_trips(&rotated));
+
+    assert_eq!(
+        rejected_at(
+            "center: (0, 0)\nunits: furlongs\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)"
+        ),
+        (ConfigErrorKind::UnexpectedToken, 2, 8)
+    );
+    assert_eq!(
+        rejected_at(
+            "center: (0, 0)\nscale: 1\nscale: 2\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)"
+        ),
+        (ConfigErrorKind::DuplicateKey, 3, 1)
+    );
+    assert_eq!(
+        rejected_at("center: (0, 0)\nscale: -1\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)"),
+        (ConfigErrorKind::InvalidNumber, 2, 8)
+    );
+}
+
+#[test]
+fn transforms() {
+    let mut sled = sled(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (1, 0) --> [arc: (1.5, 0.5)] (1, 1)",
+    );
+    let num_leds = sled.num_leds();
+    sled.transform(Affine2::from_scale(Vec2::splat(1000.0)))
+        .unwrap();
+    assert_eq!(sled.num_leds(), num_leds);
+    assert!(sled.domain().end.x > 1000.0);
+    assert!(round_trips(&sled));
+    assert!(!sled.to_config_string().contains("leds"));
+
+    // layouts without arcs can be stretched.
+    let mut stretched = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+    let num_leds = stretched.num_leds();
+    stretched
+        .transform(Affine2::from_scale(Vec2::new(2.0, 1.0)))
+        .unwrap();
+    assert_eq!(stretched.num_leds(), num_leds);
+    assert!(round_trips(&stretched));
+
+    assert!(stretched
+        .transform(Affine2::from_scale(Vec2::ZERO))
+        .is_err());
+}
+
+#[test]
+fn names_survive_round_trips() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [name: shelf] (0, 0) --> [name: left] (1, 0) [name: middle] --> (2, 0)",
+    );
+    let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    assert_eq!(reloaded.vertex_by_name("middle").unwrap().index(), 9);
+    assert_eq!(reloaded.chain_by_name("shelf").unwrap().len(), 20);
+
+    let built: Sled<Rgb> = SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(
+            Chain::new()
+                .name("shelf")
+                .vertex(Vec2::ZERO)
+                .vertex_with(Vec2::X, Segment::line().name("left"))
+                .vertex_name("middle")
+                .vertex(Vec2::new(2.0, 0.0)),
+        )
+        .build()
+        .unwrap();
+    assert_eq!(built.to_config_string(), sled.to_config_string());
+}
+
+#[test]
+fn invalid_names() {
+    let reused = Chain::new()
+        .name("a")
+        .vertex(Vec2::ZERO)
+        .vertex_name("a")
+        .vertex(Vec2::X);
+    assert!(SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(reused)
+        .build::<Rgb>()
+        .is_err());
+
+    let spaced = Chain::new().name("a b").vertex(Vec2::ZERO).vertex(Vec2::X);
+    assert!(SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(spaced)
+        .build::<Rgb>()
+        .is_err());
+
+    let err = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) [name: a] --> [name: a] (1, 0)",
+    )
+    .unwrap_err();
+    let duplicate = err.config_error().unwrap();
+    assert_eq!(
+        (duplicate.kind, duplicate.line, duplicate.column),
+        (ConfigErrorKind::DuplicateName, 4, 29)
+    );
+
+    assert_eq!(
+        rejected_at(
+            "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) [name: a] [name: b] --> (1, 0)"
+        ),
+        (ConfigErrorKind::UnexpectedToken, 4, 18)
+    );
+    // only names may follow a vertex
+    assert_eq!(
+        rejected_at("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) [leds: 3] --> (1, 0)"),
+        (ConfigErrorKind::UnknownKey, 4, 9)
+    );
+}
+
+#[test]
+fn included_groups() {
+    let sled = sled(
+        "center: (0, 0)
+        density: 30
+        include: ./benches/config.yap [name: lobby]
+        include: ./benches/config.yap [name: bar, rotation: 90, translation: (10, 0)]",
+    );
+    assert!(sled.group("lounge").is_err());
+
+    let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    assert_eq!(reloaded.group("bar").unwrap(), sled.group("bar").unwrap());
+}
+
+#[test]
+fn heights() {
+    let ramp = sled("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0, 0) --> (3, 0, 4)");
+    assert!(ramp.to_config_string().contains("(3, 0, 4)"));
+
+    let scaled = sled(
+        "center: (0, 0, 100)\nunits: cm\ndensity: 10\n--segments--\n(0, 0, 0) --> (300, 0, 400)",
+    );
+    assert_eq!(scaled.num_leds(), 50);
+    assert!(scaled.to_config_string().starts_with("center: (0, 0, 1)"));
+    assert_eq!(
+        scaled
+            .to_builder()
+            .build::<Rgb>()
+            .unwrap()
+            .to_config_string(),
+        scaled.to_config_string()
+    );
+
+    let mut moved = scaled.clone();
+    moved
+        .transform(Affine2::from_scale(Vec2::splat(2.0)))
+        .unwrap();
+    assert!(moved.to_config_string().contains("(6, 0, 8)"));
+    assert_eq!(moved.num_leds(), 50);
+
+    assert_eq!(
+        rejected_at(
+            "center: (0, 0)\ndensity: 10\ntranslation: (1, 2, 3)\n--segments--\n(0, 0) --> (1, 0)"
+        ),
+        (ConfigErrorKind::MalformedVec2, 3, 14)
+    );
+
+    // chains starting at a different height than the last one ended at aren't a lint.
+    let stacked = sled(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0, 0) --> (1, 0) | (0, 0, 1) --> (1, 0, 1)",
+    );
+    assert!(stacked.to_builder().lint().unwrap().is_empty());
+}
@@ -0,0 +1,50 @@
+#![cfg(all(feature = "drivers", feature = "std"))]
+
+use std::time::Duration;
+
+use palette::rgb::Rgb;
+use spatial_led::driver::{ConfigWatcher, Driver};
+use spatial_led::Sled;
+
+#[test]
+fn reload_keeps_the_show_running() {
+    let path = std::env::temp_dir().join(format!(
+        "spatial_led_reload_test_{}.yap",
+        std::process::id()
+    ));
+    let path = path.to_str().unwrap();
+    std::fs::write(
+        path,
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)",
+    )
+    .unwrap();
+
+    let mut driver = Driver::<Rgb>::new();
+    driver.set_startup_commands(|sled, _| {
+        sled.set_all(Rgb::new(1.0, 0.0, 0.0));
+        Ok(())
+    });
+    driver.mount(Sled::new(path).unwrap());
+    let mut watcher = ConfigWatcher::new(path).poll_interval(Duration::ZERO);
+
+    std::fs::write(
+        path,
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (2.5, 0)",
+    )
+    .unwrap();
+    assert!(driver.reload_if_changed(&mut watcher).unwrap());
+    assert!(driver.colors().all(|c| *c == Rgb::new(1.0, 0.0, 0.0)));
+
+    // a broken edit leaves the old layout mounted, and isn't retried until the file changes again.
+    std::fs::write(
+        path,
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (3, 0) -->",
+    )
+    .unwrap();
+    assert!(driver.reload_if_changed(&mut watcher).is_err());
+    assert_eq!(driver.sled().unwrap().num_leds(), 25);
+    assert!(!driver.reload_if_changed(&mut watcher).unwrap());
+
+    std::fs::remove_file(path).unwrap();
+    assert!(!driver.reload_if_changed(&mut watcher).unwrap());
+}
@@ -0,0 +1,95 @@
+use palette::rgb::Rgb;
+use spatial_led::{Sled, Vec2};
+
+const FAR_POINTS: [Vec2; 8] = [
+    Vec2::new(1e30, 1e30),
+    Vec2::new(-1e30, 0.0),
+    Vec2::new(0.0, -1e30),
+    Vec2::new(f32::MAX, f32::MIN),
+    Vec2::new(1e6, 0.5),
+    Vec2::new(-1e6, -1e6),
+    Vec2::new(f32::INFINITY, 0.0),
+    Vec2::new(0.0, f32::NEG_INFINITY),
+];
+
+fn layouts() -> [Sled<Rgb>; 2] {
+    [
+        Sled::new("./benches/config.yap").unwrap(),
+        Sled::new_from_str(
+            "center: (0, 0)
+            density: 20
+            --segments--
+            (-2, 0) --> [arc: (0, 2)] (2, 0) --> [bezier: (3, -3) (-3, -3)] (-2, 0)",
+        )
+        .unwrap(),
+    ]
+}
+
+/// Returns how far the LED closest to or furthest from `pos` is, squared, found by checking every LED.
+///
+/// Far from the layout, f32 rounding leaves many LEDs equally distant, so tests compare
+/// distances rather than indices.
+fn brute_force(sled: &Sled<Rgb>, pos: Vec2, furthest: bool) -> f32 {
+    let distances = sled.leds().map(|led| led.position().distance_squared(pos));
+    if furthest {
+        distances.fold(f32::NEG_INFINITY, f32::max)
+    } else {
+        distances.fold(f32::INFINITY, f32::min)
+    }
+}
+
+fn dist_sq(sled: &Sled<Rgb>, index: usize, pos: Vec2) -> f32 {
+    sled.get(index).unwrap().position().distance_squared(pos)
+}
+
+#[test]
+fn closest_to_far_points() {
+    for sled in layouts() {
+        for pos in FAR_POINTS {
+            let closest = sled.index_of_closest_to(pos);
+            assert_eq!(
+                dist_sq(&sled, closest, pos),
+                brute_force(&sled, pos, false),
+                "closest to {pos}"
+            );
+            assert_eq!(sled.k_closest_to(pos, 3).len(), 3, "3 closest to {pos}");
+        }
+    }
+}
+
+#[test]
+fn furthest_from_far_points() {
+    for sled in layouts() {
+        for pos in FAR_POINTS {
+            let furthest = sled.index_of_furthest_from(pos);
+            assert_eq!(
+                dist_sq(&sled, furthest, pos),
+                brute_force(&sled, pos, true),
+                "furthest from {pos}"
+            );
+        }
+    }
+}
+
+#[test]
+fn within_dist_of_far_points() {
+    for sled in layouts() {
+        for pos in FAR_POINTS {
+            assert!(sled.within_dist_from(10.0, pos).is_empty(), "near {pos}");
+        }
+        assert_eq!(
+            sled.within_dist_from(2e6, Vec2::new(1e6, 0.0)).len(),
+            sled.num_leds()
+        );
+    }
+}
+
+#[test]
+fn nan_points_do_not_panic() {
+    for sled in layouts() {
+        let pos = Vec2::new(f32::NAN, 0.0);
+        assert!(sled.index_of_closest_to(pos) < sled.num_leds());
+        assert!(sled.k_closest_to(pos, 2).len() <= 2);
+        assert!(sled.within_dist_from(1.0, pos).is_empty());
+    }
+}
@@ -0,0 +1,146 @@
+#![cfg(feature = "std")]
+
+use std::fs;
+use std::path::PathBuf;
+
+use palette::rgb::Rgb;
+use spatial_led::{ConfigErrorKind, Sled, Vec2};
+
+/// A scratch directory of layout files, removed again once the test is done with it.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("spatial_led_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("rooms")).unwrap();
+        Scratch(dir)
+    }
+
+    fn write(&self, file: &str, contents: &str) -> &Self {
+        fs::write(self.0.join(file), contents).unwrap();
+        self
+    }
+
+    fn load(&self, file: &str) -> Result<Sled<Rgb>, spatial_led::SledError> {
+        Sled::new(self.path(file).to_str().unwrap())
+    }
+
+    fn path(&self, file: &str) -> PathBuf {
+        self.0.join(file)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+const KITCHEN: &str =
+    "center: (0, 0)\ndensity: 10\n--segments--\n[name: counter] (0, 0) --> (1, 0)";
+
+#[test]
+fn nested_includes_are_grouped_and_placed() {
+    let scratch = Scratch::new("nested_includes");
+    scratch
+        .write("rooms/kitchen.yap", KITCHEN)
+        .write(
+            "venue.yap",
+            "center: (0, 0)\ndensity: 10\ninclude: rooms/kitchen.yap\n--segments--\n(5, 5) --> [name: counter] (6, 5)",
+        )
+        .write(
+            "city.yap",
+            "center: (0, 0)\ndensity: 20\ninclude: venue.yap [scale: 2]",
+        );
+
+    let city = scratch.load("city.yap").unwrap();
+    assert!(city.groups().eq(["venue", "venue/kitchen"]));
+    assert_eq!(city.group("venue").unwrap().len(), 20);
+    assert!(city.group("venue/kitchen").unwrap().into_iter().eq(10..20));
+    assert!(city
+        .chain_by_name("venue/kitchen/counter")
+        .unwrap()
+        .into_iter()
+        .eq(10..20));
+    assert!(city
+        .segment_by_name("venue/counter")
+        .unwrap()
+        .into_iter()
+        .eq(0..10));
+    assert!(city
+        .get(19)
+        .unwrap()
+        .position()
+        .abs_diff_eq(Vec2::new(2.0, 0.0), 1e-5));
+
+    let reloaded = Sled::<Rgb>::new_from_str(&city.to_config_string()).unwrap();
+    assert!(reloaded.positions().eq(city.positions()));
+}
+
+#[test]
+fn cyclic_include_reports_its_line() {
+    let scratch = Scratch::new("cyclic_include");
+    scratch.write(
+        "loop.yap",
+        "center: (0, 0)\ndensity: 10\n  include: ./loop.yap",
+    );
+
+    let err = scratch.load("loop.yap").unwrap_err();
+    assert!(err.message.contains("includes itself"));
+
+    let config_err = err.config_error().unwrap();
+    assert_eq!(config_err.kind, ConfigErrorKind::CyclicInclude);
+    assert_eq!((config_err.line, config_err.column), (3, 12));
+    assert_eq!(config_err.found, "./loop.yap");
+}
+
+#[test]
+fn missing_include_reports_its_line() {
+    let err =
+        Sled::<Rgb>::new_from_str("center: (0, 0)\ndensity: 10\ninclude: ./benches/missing.yap")
+            .unwrap_err();
+
+    let config_err = err.config_error().unwrap();
+    assert_eq!(config_err.kind, ConfigErrorKind::InvalidInclude);
+    assert_eq!((config_err.line, config_err.column), (3, 10));
+    assert_eq!(config_err.found, "./benches/missing.yap");
+}
+
+#[test]
+fn repeated_group_is_rejected() {
+    let err = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)\ndensity: 10\ninclude: ./benches/config.yap\ninclude: ./benches/config.yap",
+    )
+    .unwrap_err();
+
+    let config_err = err.config_error().unwrap();
+    assert_eq!(config_err.kind, ConfigErrorKind::DuplicateName);
+    assert_eq!((config_err.line, config_err.column), (4, 10));
+    assert_eq!(config_err.found, "config");
+}
+
+#[test]
+fn errors_in_included_files_name_the_file() {
+    let scratch = Scratch::new("broken_include");
+    scratch
+        .write("rooms/kitchen.yap", KITCHEN)
+        .write(
+            "broken.yap",
+            "center: (0, 0)\ndensity: 10\ninclude: rooms/kitchen.yap [colour: red]",
+        )
+        .write(
+            "typo.yap",
+            "center: (0, 0)\ndensity: 10\ninclude: broken.yap",
+        );
+
+    assert!(scratch
+        .load("broken.yap")
+        .unwrap_err()
+        .config_error()
+        .is_some());
+
+    let err = scratch.load("typo.yap").unwrap_err();
+    assert!(err.message.starts_with("In `broken.yap`"));
+    assert!(err.config_error().is_some());
+}
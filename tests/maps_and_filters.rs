@@ -0,0 +1,50 @@
+use palette::rgb::Rgb;
+use spatial_led::{Sled, Vec2};
+
+fn square() -> Sled<Rgb> {
+    Sled::new_from_str(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)",
+    )
+    .unwrap()
+}
+
+#[test]
+fn map_by_dist_to_line() {
+    let mut sled = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+    sled.map_by_dist_to_line(Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0), |dist| {
+        let brightness = (0.5 + dist * 4.0).clamp(0.0, 1.0);
+        Rgb::new(brightness, brightness, brightness)
+    });
+    assert!(sled
+        .leds()
+        .all(|led| (led.color.red == 1.0) == (led.position().x <= 0.875)));
+
+    // distances are signed, positive to the left of the line.
+    sled.map_by_dist_to_line(Vec2::new(0.0, 0.0), Vec2::new(2.0, 2.0), |dist| {
+        Rgb::new(dist, 0.0, 0.0)
+    });
+    assert!(sled.leds().all(|led| {
+        let expected = (led.position().y - led.position().x) / 2f32.sqrt();
+        (led.color.red - expected).abs() < 1e-5
+    }));
+
+    // a line with no length measures distance to its point.
+    sled.map_by_dist_to_line(Vec2::ONE, Vec2::ONE, |dist| Rgb::new(dist, 0.0, 0.0));
+    assert!(sled
+        .leds()
+        .all(|led| (led.color.red - led.position().distance(Vec2::ONE)).abs() < 1e-5));
+}
+
+#[test]
+fn filter_half_plane() {
+    let sled = square();
+
+    let right_side = sled.filter_half_plane(Vec2::new(2.05, 0.0), Vec2::new(1.0, 0.0));
+    assert_eq!(right_side, sled.filter_by_pos(|pos| pos.x >= 2.05));
+
+    let above_diagonal = sled.filter_half_plane(Vec2::ZERO, Vec2::new(-3.0, 3.0));
+    assert_eq!(above_diagonal, sled.filter_by_pos(|pos| pos.y >= pos.x));
+}
@@ -0,0 +1,258 @@
+use core::f32::consts::{FRAC_PI_4, PI};
+
+use palette::rgb::Rgb;
+use spatial_led::{Rect, Sled, Vec2};
+
+/// A 4m x 4m room with LEDs running around its walls, 40 to a wall.
+fn room(center: Vec2) -> Sled<Rgb> {
+    Sled::new_from_str(&format!(
+        "center: ({}, {})
+        density: 10
+        --segments--
+        (0, 0) --> (4, 0) --> (4, 4) --> (0, 4) --> (0, 0)",
+        center.x, center.y
+    ))
+    .unwrap()
+}
+
+fn count(sled: &Sled<Rgb>, predicate: impl Fn(&Rgb) -> bool) -> usize {
+    sled.colors().filter(|color| predicate(color)).count()
+}
+
+#[test]
+fn k_closest_to_matches_a_sort_by_distance() {
+    let mut sled = room(Vec2::ZERO);
+    let particle = Vec2::new(1.03, 0.2);
+
+    let mut by_dist: Vec<u32> = (0..sled.num_leds() as u32).collect();
+    by_dist.sort_by(|a, b| {
+        let dist = |i: &u32| {
+            sled.get(*i as usize)
+                .unwrap()
+                .position()
+                .distance_squared(particle)
+        };
+        dist(a).partial_cmp(&dist(b)).unwrap().then(a.cmp(b))
+    });
+    for k in [0, 1, 7, 40] {
+        let mut expected = by_dist[..k].to_vec();
+        expected.sort();
+        assert!(sled.k_closest_to(particle, k).into_iter().eq(expected));
+    }
+    assert_eq!(sled.k_closest_to(particle, 1000).len(), sled.num_leds());
+    assert!(sled
+        .k_closest_to(Vec2::new(100.0, -50.0), 1)
+        .into_iter()
+        .eq([39]));
+
+    assert!(sled.set_k_closest_to(particle, 3, Rgb::new(1.0, 1.0, 1.0)));
+    assert_eq!(count(&sled, |c| c.red == 1.0), 3);
+    assert!(sled.modulate_k_closest_to(particle, 5, |led| led.color * 0.5));
+    assert!(!sled.set_k_closest_to(particle, 0, Rgb::new(0.0, 0.0, 0.0)));
+    assert_eq!(count(&sled, |c| c.red == 0.5), 3);
+}
+
+#[test]
+fn at_line() {
+    let mut sled = room(Vec2::ZERO);
+    sled.set_at_line(
+        Vec2::new(2.05, -1.0),
+        Vec2::new(2.05, 5.0),
+        Rgb::new(1.0, 0.0, 0.0),
+    );
+
+    assert!(sled
+        .at_line(Vec2::new(2.05, -1.0), Vec2::new(2.05, 1.0))
+        .into_iter()
+        .eq([20]));
+    assert!(sled
+        .at_line(Vec2::new(1.0, 1.0), Vec2::new(3.0, 3.0))
+        .is_empty());
+    assert!(sled
+        .at_line(Vec2::new(1.0, 1.0), Vec2::new(1.0, 1.0))
+        .is_empty());
+    assert!(!sled.set_at_line(
+        Vec2::new(1.0, 1.0),
+        Vec2::new(3.0, 3.0),
+        Rgb::new(1.0, 0.0, 0.0)
+    ));
+    assert_eq!(count(&sled, |c| c.red == 1.0), 2);
+
+    assert!(
+        sled.modulate_at_line(Vec2::new(-1.0, 2.05), Vec2::new(5.0, 2.05), |led| {
+            led.color + Rgb::new(0.0, 1.0, 0.0)
+        })
+    );
+    assert_eq!(count(&sled, |c| c.green == 1.0), 2);
+}
+
+#[test]
+fn within_polygon() {
+    let mut sled = room(Vec2::ZERO);
+    let bar = [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(3.05, -1.0),
+        Vec2::new(3.05, 0.5),
+        Vec2::new(0.5, 0.5),
+        Vec2::new(0.5, 2.95),
+        Vec2::new(-1.0, 2.95),
+    ];
+
+    let expected = sled.filter_by_pos(|p| (p.x < 3.05 && p.y < 0.5) || (p.x < 0.5 && p.y < 2.95));
+    assert_eq!(sled.within_polygon(&bar), expected);
+    let reversed: Vec<Vec2> = bar.iter().rev().copied().collect();
+    assert_eq!(sled.within_polygon(&reversed), expected);
+    assert!(sled.within_polygon(&bar[..2]).is_empty());
+    let elsewhere = [
+        Vec2::new(10.0, 10.0),
+        Vec2::new(11.0, 10.0),
+        Vec2::new(10.0, 11.0),
+    ];
+    assert!(sled.within_polygon(&elsewhere).is_empty());
+
+    assert!(sled.set_within_polygon(&bar, Rgb::new(1.0, 0.5, 0.0)));
+    assert_eq!(count(&sled, |c| *c == Rgb::new(1.0, 0.5, 0.0)), 60);
+    assert!(sled.modulate_within_polygon(&bar, |led| led.color * 0.5));
+    assert!(!sled.set_within_polygon(&bar[..2], Rgb::new(0.0, 0.0, 0.0)));
+
+    sled.map_within_polygon(&bar, |pos| Rgb::new(pos.x, pos.y, 0.0));
+    let inside = sled.get(5).unwrap();
+    assert_eq!(inside.color, Rgb::new(inside.position().x, 0.0, 0.0));
+    assert_eq!(sled.get(35).unwrap().color, Rgb::new(0.0, 0.0, 0.0));
+
+    // self-intersecting polygons follow the even-odd rule.
+    let bowtie = [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(5.0, 5.0),
+        Vec2::new(5.0, -1.0),
+        Vec2::new(-1.0, 5.0),
+    ];
+    assert!(!sled.within_polygon(&bowtie).into_iter().any(|i| i == 20));
+    assert!(sled.within_polygon(&bowtie).into_iter().any(|i| i == 60));
+}
+
+#[test]
+fn within_rect() {
+    let mut sled = room(Vec2::ZERO);
+    let corner = Rect::new(Vec2::new(3.45, -0.5), Vec2::new(4.5, 1.05));
+    let elsewhere = Rect::new(Vec2::splat(5.0), Vec2::splat(6.0));
+
+    assert_eq!(
+        sled.within_rect(corner),
+        sled.filter_by_pos(|p| p.x >= 3.45 && p.y <= 1.05)
+    );
+    assert_eq!(
+        sled.within_rect(sled.domain().into()).len(),
+        sled.num_leds()
+    );
+    assert!(sled.within_rect(elsewhere).is_empty());
+
+    assert!(sled.map_within_rect(corner, |pos| Rgb::new(pos.y, 0.0, 0.0)));
+    assert_eq!(sled.get(49).unwrap().color, Rgb::new(1.0, 0.0, 0.0));
+    assert!(sled.set_within_rect(corner, Rgb::new(0.0, 1.0, 0.0)));
+    assert_eq!(count(&sled, |c| c.green == 1.0), 16);
+    assert!(sled.modulate_within_rect(corner, |led| led.color * 0.5));
+    assert_eq!(sled.get(40).unwrap().color, Rgb::new(0.0, 0.5, 0.0));
+    assert!(!sled.set_within_rect(elsewhere, Rgb::new(0.0, 1.0, 0.0)));
+}
+
+#[test]
+fn within_sector() {
+    let center = Vec2::new(2.05, 2.05);
+    let mut sled = room(center);
+
+    let beam = sled.within_sector(-FRAC_PI_4, FRAC_PI_4);
+    assert_eq!(
+        beam,
+        sled.filter_by_pos(|p| (p.y - center.y).abs() <= p.x - center.x)
+    );
+    assert_eq!(sled.within_sector(-PI, PI).len(), sled.num_leds());
+    assert_eq!(sled.within_sector(0.0, 10.0).len(), sled.num_leds());
+
+    let upper = sled.within_sector_from(Vec2::new(2.0, 2.0), 0.0, PI);
+    assert_eq!(upper, sled.filter_by_pos(|p| p.y >= 2.0));
+    let lower = sled.within_sector_from(Vec2::new(2.0, 2.0), PI, 0.0);
+    assert_eq!(lower, sled.filter_by_pos(|p| p.y <= 2.0));
+    assert!(sled
+        .within_sector_from(Vec2::new(10.0, 2.0), -FRAC_PI_4, FRAC_PI_4)
+        .is_empty());
+
+    assert!(sled.set_within_sector(-FRAC_PI_4, FRAC_PI_4, Rgb::new(0.0, 1.0, 0.0)));
+    assert_eq!(count(&sled, |c| c.green == 1.0), beam.len());
+    assert!(!sled.set_within_sector_from(
+        Vec2::new(10.0, 2.0),
+        -FRAC_PI_4,
+        FRAC_PI_4,
+        Rgb::new(1.0, 1.0, 1.0)
+    ));
+    assert!(
+        sled.modulate_within_sector_from(Vec2::new(2.0, 2.0), 0.0, PI, |led| {
+            led.color + Rgb::new(1.0, 0.0, 0.0)
+        })
+    );
+    assert_eq!(count(&sled, |c| c.red == 1.0), upper.len());
+}
+
+#[test]
+fn within_annulus() {
+    let mut sled = room(Vec2::new(2.0, 2.0));
+
+    let ripple = sled.within_annulus_from(Vec2::ZERO, 2.75, 3.25);
+    assert_eq!(
+        ripple,
+        sled.filter_by_pos(|p| (p.length() - 3.0).abs() < 0.25)
+    );
+    assert_eq!(sled.within_annulus(0.0, 2.5), sled.within_dist(2.5));
+
+    let inner = sled.within_annulus(1.0, 2.2);
+    let outer = sled.within_annulus(2.2, 5.0);
+    assert!(!inner.is_empty() && !outer.is_empty());
+    assert!(inner.and(&outer).is_empty());
+    assert_eq!(inner.or(&outer).len(), sled.num_leds());
+    assert!(sled.within_annulus(2.0, 1.0).is_empty());
+    assert!(sled
+        .within_annulus_from(Vec2::new(20.0, 20.0), 1.0, 2.0)
+        .is_empty());
+
+    assert!(!sled.set_within_annulus_from(
+        Vec2::new(20.0, 20.0),
+        1.0,
+        2.0,
+        Rgb::new(1.0, 1.0, 1.0)
+    ));
+    assert!(sled.set_within_annulus(2.2, 5.0, Rgb::new(1.0, 0.0, 0.0)));
+    assert!(sled.modulate_within_annulus(1.0, 2.2, |led| led.color + Rgb::new(0.0, 1.0, 0.0)));
+    assert_eq!(count(&sled, |c| c.red == 1.0), outer.len());
+    assert_eq!(count(&sled, |c| c.green == 1.0), inner.len());
+    assert!(sled.modulate_within_annulus_from(Vec2::ZERO, 0.0, 1.0, |led| led.color));
+}
+
+#[test]
+fn copy_colors_by_position() {
+    let mut old =
+        Sled::<Rgb>::new_from_str("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)")
+            .unwrap();
+    old.set_all(Rgb::new(1.0, 0.0, 0.0));
+
+    let mut new = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 1) --> (1, 1) | (0, 0) --> (1, 0)",
+    )
+    .unwrap();
+    new.copy_colors_by_position(&old);
+
+    assert!(new.colors().skip(10).all(|c| *c == Rgb::new(1.0, 0.0, 0.0)));
+    assert!(new.colors().take(10).all(|c| *c == Rgb::new(0.0, 0.0, 0.0)));
+}
+
+#[test]
+fn rects() {
+    let bar = Rect::new(Vec2::new(2.0, 1.0), Vec2::new(-1.0, 0.0));
+
+    assert_eq!(
+        Rect::from_center_size(Vec2::new(0.5, 0.5), Vec2::new(3.0, 1.0)),
+        bar
+    );
+    assert_eq!(Rect::from(Vec2::new(-1.0, 0.0)..Vec2::new(2.0, 1.0)), bar);
+    assert!(bar.intersects(&Rect::new(Vec2::new(2.0, 1.0), Vec2::new(3.0, 3.0))));
+    assert!(!bar.intersects(&Rect::new(Vec2::new(2.1, 1.0), Vec2::new(3.0, 3.0))));
+}
@@ -0,0 +1,113 @@
+use palette::rgb::Rgb;
+use spatial_led::{Chain, Segment, Sled, SledBuilder, Vec2};
+
+fn tagged(tags: &str) -> Result<Sled<Rgb>, spatial_led::SledError> {
+    Sled::new_from_str(&format!(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [tags: {}] (1, 0)",
+        tags
+    ))
+}
+
+#[test]
+fn names() {
+    let sled = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [name: room] (0, 0) [name: door] --> (2, 0) [name: corner]
+        --> [name: north_wall] (2, 2) --> (0, 2)",
+    )
+    .unwrap();
+
+    let north_wall = sled.segment_by_name("north_wall").unwrap();
+    assert!(north_wall.into_iter().eq(20..40));
+    assert_eq!(sled.vertex_by_name("door").unwrap().index(), 0);
+    assert_eq!(
+        sled.vertex_by_name("corner").unwrap().position(),
+        Vec2::new(2.0, 0.0)
+    );
+    assert_eq!(sled.chain_by_name("room").unwrap().len(), 60);
+
+    assert!(sled.named("corner").unwrap().into_iter().eq([19]));
+    assert_eq!(sled.named("room").unwrap().len(), 60);
+
+    // names only match things of the kind asked for.
+    assert!(sled.segment_by_name("south_wall").is_err());
+    assert!(sled.segment_by_name("room").is_err());
+    assert_eq!(
+        sled.segment_by_name("nowhere").unwrap_err().message,
+        "No segment named `nowhere` exists."
+    );
+}
+
+#[test]
+fn tags() {
+    let mut sled = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [tags: zone=bar] (0, 0) --> [tags: facing=down diffused] (1, 0) --> (1, 1) |
+        (2, 0) --> [tags: zone=stage] (3, 0)",
+    )
+    .unwrap();
+
+    let tags = sled.tags_of(5).unwrap();
+    assert_eq!(tags["zone"], "bar");
+    assert_eq!(tags["facing"], "down");
+    assert_eq!(tags["diffused"], "true");
+    assert!(sled.tags_of(15).unwrap().get("facing").is_none());
+    assert_eq!(sled.tags_of(25).unwrap().len(), 1);
+    assert!(sled.tags_of(30).is_none());
+
+    let bar = sled.filter_by_tag("zone", "bar");
+    assert_eq!(bar.len(), 20);
+    assert!(sled.filter_by_tag("zone", "stage").into_iter().eq(20..30));
+    assert!(sled.filter_by_tag("zone", "lobby").is_empty());
+    sled.set_filter(&bar, Rgb::new(1.0, 0.5, 0.0));
+
+    let reloaded = Sled::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    assert!((0..sled.num_leds()).all(|i| reloaded.tags_of(i) == sled.tags_of(i)));
+}
+
+#[test]
+fn invalid_tags() {
+    for tags in ["a=1 a=2", "=1", "a="] {
+        assert!(tagged(tags).is_err(), "{tags}");
+    }
+    assert!(Sled::<Rgb>::new_from_str(
+        "center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> [tags] (1, 0)"
+    )
+    .is_err());
+}
+
+#[test]
+fn segment_tags_override_chain_tags() {
+    let sled: Sled<Rgb> = SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(
+            Chain::new()
+                .tag("zone", "bar")
+                .vertex(Vec2::new(0.0, 0.0))
+                .vertex_with(Vec2::new(1.0, 0.0), Segment::line().tag("zone", "stage"))
+                .vertex(Vec2::new(1.0, 1.0)),
+        )
+        .build()
+        .unwrap();
+
+    assert!(sled.filter_by_tag("zone", "stage").into_iter().eq(0..10));
+    assert!(sled.filter_by_tag("zone", "bar").into_iter().eq(10..20));
+}
+
+#[test]
+fn invalid_builder_tags() {
+    let with_key = SledBuilder::new(Vec2::ZERO, 10.0).chain(
+        Chain::new()
+            .tag("a=b", "c")
+            .vertex(Vec2::ZERO)
+            .vertex(Vec2::X),
+    );
+    assert!(with_key.build::<Rgb>().is_err());
+
+    let with_value = SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(Chain::from_vertices([Vec2::ZERO, Vec2::X]).tag("a", ""));
+    assert!(with_value.build::<Rgb>().is_err());
+}
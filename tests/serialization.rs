@@ -0,0 +1,22 @@
+#![cfg(feature = "serde")]
+
+use spatial_led::Sled;
+
+#[test]
+fn names_and_tags_survive_serialization() {
+    let named = Sled::<(u8, u8, u8)>::new_from_str(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [name: c] (0, 0) [name: v] --> [name: s, tags: zone=bar lit] (1, 0)",
+    )
+    .unwrap();
+
+    let json = serde_json::to_string(&named).unwrap();
+    let restored: Sled<(u8, u8, u8)> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(restored.to_config_string(), named.to_config_string());
+    for name in ["c", "v", "s"] {
+        assert!(restored.named(name).is_ok(), "{name}");
+    }
+}
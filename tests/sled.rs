@@ -0,0 +1,62 @@
+use palette::rgb::Rgb;
+use spatial_led::{Chain, Sled, SledBuilder, Vec2};
+
+#[test]
+fn outputs_keep_their_own_colors() {
+    let mut sled = Sled::<Rgb>::new_from_str(
+        "center: (0, 0)
+        density: 10
+        --segments--
+        [output: left_wall] (-1, 0) --> (-1, 1) |
+        [output: right_wall] (1, 0) --> (1, 1)",
+    )
+    .unwrap();
+    sled.set_within_dist_from(1.0, Vec2::new(1.0, 0.0), Rgb::new(1.0, 0.0, 0.0));
+
+    for name in ["left_wall", "right_wall"] {
+        let colors: Vec<Rgb> = sled.colors_for_output(name).unwrap().copied().collect();
+        assert_eq!(colors.len(), 10);
+        assert_eq!(colors[0].red, if name == "right_wall" { 1.0 } else { 0.0 });
+    }
+}
+
+#[test]
+fn moving_the_center_point() {
+    let mut sled =
+        Sled::<Rgb>::new_from_str("center: (0, 0)\ndensity: 10\n--segments--\n(0, 0) --> (1, 0)")
+            .unwrap();
+    sled.set_center_point(Vec2::new(1.0, 0.0));
+
+    assert_eq!(sled.furthest().index(), 0);
+    assert_eq!(sled.index_of_furthest(), 0);
+    assert!((sled.get(0).unwrap().angle() - core::f32::consts::PI).abs() < 1e-5);
+    assert_eq!(sled.within_dist(0.25).len(), 3);
+}
+
+#[test]
+fn large_layouts() {
+    // a facade with 100 rows of 1,000 LEDs each
+    let facade: Sled<Rgb> = (0..100)
+        .fold(SledBuilder::new(Vec2::ZERO, 100.0), |builder, row| {
+            let y = row as f32 * 0.1;
+            builder.chain(Chain::from_vertices([
+                Vec2::new(0.0, y),
+                Vec2::new(10.0, y),
+            ]))
+        })
+        .build()
+        .unwrap();
+
+    assert_eq!(facade.get(99_999).unwrap().segment(), 99);
+    assert_eq!(facade.closest_to(Vec2::new(9.999, 9.9)).index(), 99_999);
+    assert_eq!(facade.segment(99).unwrap().into_iter().max(), Some(99_999));
+
+    let many_segments: Sled<Rgb> = SledBuilder::new(Vec2::ZERO, 10.0)
+        .chain(Chain::from_vertices(
+            (0..2000).map(|i| Vec2::new(i as f32, (i % 2) as f32)),
+        ))
+        .build()
+        .unwrap();
+    assert_eq!(many_segments.num_segments(), 1999);
+    assert_eq!(many_segments.leds().last().unwrap().segment(), 1998);
+}
@@ -0,0 +1,150 @@
+#![cfg(feature = "3d")]
+
+use palette::rgb::Rgb;
+use spatial_led::{Chain, Sled, Sled3, SledBuilder, Vec2, Vec3};
+
+/// A pole 2m tall, with a strip wound once around it.
+fn pole() -> Sled3<Rgb> {
+    Sled3::new_from_str(
+        "center: (0, 0, 1)
+        density: 10
+        --segments--
+        (0, 0, 0) --> (0, 0, 2) |
+        (1, 0, 0) --> [arc: (-1, 0)] (1, 0, 2)",
+    )
+    .unwrap()
+}
+
+#[test]
+fn heights() {
+    let mut sled = pole();
+
+    assert_eq!(sled.segment(1).unwrap().len(), 66);
+    assert_eq!(sled.num_leds(), 86);
+    assert!(sled
+        .leds()
+        .skip(20)
+        .all(|led| (led.position().truncate().length() - 1.0).abs() < 1e-3));
+    assert!(sled
+        .domain()
+        .end
+        .abs_diff_eq(Vec3::new(1.0, 1.0, 2.0), 1e-2));
+
+    assert_eq!(sled.closest().index(), 9);
+    assert!(sled.get(9).unwrap().distance().abs() < 1e-5);
+    assert!(sled.furthest().position().z > 1.9);
+    assert!(sled
+        .get(20)
+        .unwrap()
+        .direction()
+        .abs_diff_eq(Vec3::new(1.0, 0.0, -1.0).normalize(), 0.1));
+    assert_eq!(sled.furthest_from(Vec3::new(0.0, 0.0, 2.0)).index(), 20);
+    assert_eq!(
+        sled.filter_by_dist_from(Vec3::ZERO, |dist| dist < 0.15)
+            .len(),
+        1
+    );
+
+    sled.map_by_pos(|pos| Rgb::new(pos.z / 2.0, 0.0, 0.0));
+    assert_eq!(sled.get(19).unwrap().color, Rgb::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn layouts_are_seen_from_above() {
+    let sled = pole();
+    let planar = sled.layout();
+
+    assert_eq!(planar.num_leds(), sled.num_leds());
+    assert!(planar
+        .positions()
+        .zip(sled.positions())
+        .all(|(a, b)| a == b.truncate()));
+}
+
+#[test]
+fn config_strings_round_trip() {
+    let sled = pole();
+    let reloaded = Sled3::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+
+    assert!(reloaded.positions().eq(sled.positions()));
+    assert_eq!(reloaded.center_point(), Vec3::new(0.0, 0.0, 1.0));
+}
+
+#[test]
+fn planar_configs() {
+    let flat = Sled3::<Rgb>::new("./benches/config.yap").unwrap();
+    let flat_2d = Sled::<Rgb>::new("./benches/config.yap").unwrap();
+
+    assert!(flat
+        .positions()
+        .zip(flat_2d.positions())
+        .all(|(a, b)| a == b.extend(0.0)));
+    assert!(flat
+        .leds()
+        .zip(flat_2d.leds())
+        .all(|(a, b)| (a.distance() - b.distance()).abs() < 1e-5));
+    assert_eq!(flat.to_config_string(), flat_2d.to_config_string());
+}
+
+#[test]
+fn invalid_heights() {
+    for segments in [
+        "(0, 0) --> [arc: (1, 1, 1)] (2, 0)",
+        "(0, 0, 0, 0) --> (2, 0)",
+    ] {
+        let config = format!("center: (0, 0)\ndensity: 10\n--segments--\n{}", segments);
+        assert!(Sled3::<Rgb>::new_from_str(&config).is_err(), "{segments}");
+    }
+
+    let nan = SledBuilder::new(Vec2::ZERO, 10.0).chain(
+        Chain::new()
+            .vertex(Vec2::ZERO)
+            .vertex(Vec2::X)
+            .vertex_z(f32::NAN),
+    );
+    assert!(Sled3::<Rgb>::from_builder(nan).is_err());
+}
+
+#[test]
+fn builders_keep_heights() {
+    let sled = Sled3::<Rgb>::from_builder(
+        SledBuilder::new(Vec2::ZERO, 10.0).center_z(1.0).chain(
+            Chain::new()
+                .vertex(Vec2::ZERO)
+                .vertex(Vec2::ZERO)
+                .vertex_z(2.0),
+        ),
+    )
+    .unwrap();
+
+    let reloaded = Sled3::<Rgb>::new_from_str(&sled.to_config_string()).unwrap();
+    assert_eq!(reloaded.num_leds(), 20);
+    assert!(sled
+        .layout()
+        .to_builder()
+        .build::<Rgb>()
+        .unwrap()
+        .to_config_string()
+        .contains("(0, 0, 2)"));
+}
+
+#[test]
+fn furthest_ties_go_to_the_lowest_index() {
+    let mut sled = Sled3::<Rgb>::new_from_str(
+        "center: (0, 0, 0)
+        density: 10
+        --segments--
+        (0, 0, 1) --> [leds: 1] (0, 0, 2) |
+        (0, 0, -1) --> [leds: 1] (0, 0, -2)",
+    )
+    .unwrap();
+    assert_eq!(
+        sled.get(0).unwrap().distance(),
+        sled.get(1).unwrap().distance()
+    );
+
+    assert_eq!(sled.furthest().index(), 0);
+    assert_eq!(sled.furthest_from(Vec3::ZERO).index(), 0);
+    sled.set_center_point(Vec3::new(1.0, 0.0, 0.0));
+    assert_eq!(sled.furthest().index(), 0);
+}